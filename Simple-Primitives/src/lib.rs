@@ -12,14 +12,14 @@ use web_sys::{
 };
 use std::cell::RefCell;
 use std::rc::Rc;
-use std::f32::consts::PI;
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, FRAC_PI_8, PI};
 
 #[allow(dead_code)]
 mod utils;
 pub mod shapes;
 use utils::{compile_shader, link_program, log};
 use shapes::{
-    generate_geometry, export_shape
+    ShapeData, ShapeParams, ParamKind, parse_obj, generate_geometry, usize2Shape, export_shape, export_material, export_shape_stl, export_shape_gltf, export_shape_ply, ColorScheme, ShapeError, LIGHT_GRAY
 };
 
 // Returns the canvas element with the id canvas.
@@ -43,16 +43,108 @@ pub fn get_webgl_context() -> Result<WebGlRenderingContext, JsValue> {
     Ok(gl)
 }
 
-// Exports the shape as OBJ format.
+// Moves and scales the shape so it is centered on the origin and its largest side is 2 long, the same size as the sphere. This way any OBJ file fits in the view.
+fn fit_in_view(shape_data: &mut ShapeData) {
+    let mut min = [f32::MAX; 3];
+    let mut max = [f32::MIN; 3];
+    for vertex in shape_data.vertices.chunks(3) {
+        for i in 0..3 {
+            min[i] = min[i].min(vertex[i]);
+            max[i] = max[i].max(vertex[i]);
+        }
+    }
+
+    let size = (0..3).map(|i| max[i] - min[i]).fold(0.0, f32::max);
+    if size <= 0.0 {
+        return;
+    }
+
+    for vertex in shape_data.vertices.chunks_mut(3) {
+        for i in 0..3 {
+            vertex[i] = (vertex[i] - (min[i] + max[i]) / 2.0) * 2.0 / size;
+        }
+    }
+}
+
+// Returns the names of all shapes in the order of their index, which the editor uses to fill the shape dropdown.
 #[wasm_bindgen]
-pub fn _export_shape(shapeIndex: usize, scale: Vec<f32>, args: Vec<f32>) -> String {
-    return export_shape(shapeIndex, scale, args);
+pub fn _shape_names() -> js_sys::Array {
+    (0..).map_while(|index| usize2Shape(index).ok())
+        .map(|shape| JsValue::from_str(shape.name()))
+        .collect()
 }
 
-// The webclient struct is responsible for drawing the generated geomtry on the html canvas. It uses a simple shader where the triangles have distinc colors so that the user can differentiate them from each other.
+// Returns the parameters of the shape as an array of objects with a name, label, type ("int", "float", "choice" or "bool"), default, min and max. Max is Infinity when there is no upper limit.
+// A choice also has an array of options, its value is the index of the chosen option.
+#[wasm_bindgen]
+pub fn _shape_schema(shapeIndex: usize) -> Result<js_sys::Array, ShapeError> {
+    let schema = js_sys::Array::new();
+
+    for info in usize2Shape(shapeIndex)?.params() {
+        let object = js_sys::Object::new();
+        let set = |key: &str, value: JsValue| js_sys::Reflect::set(&object, &JsValue::from_str(key), &value).unwrap();
+        set("name", JsValue::from_str(info.name));
+        set("label", JsValue::from_str(info.label));
+        set("type", JsValue::from_str(info.kind.name()));
+        set("default", JsValue::from_f64(info.default as f64));
+        set("min", JsValue::from_f64(info.min as f64));
+        set("max", JsValue::from_f64(info.max as f64));
+        if let ParamKind::Choice(options) = info.kind {
+            set("options", options.iter().map(|&option| JsValue::from_str(option)).collect::<js_sys::Array>().into());
+        }
+        schema.push(&object);
+    }
+
+    Ok(schema)
+}
+
+// Exports the shape as OBJ format. When material_file is not empty the OBJ file references it as its .mtl file.
+// The params of this and the other export functions is an object with a property for every parameter in the schema of the shape.
+#[wasm_bindgen]
+pub fn _export_shape(shapeIndex: usize, scale: Vec<f32>, params: JsValue, precision: usize, material_file: String) -> Result<String, ShapeError> {
+    export_shape(&ShapeParams::from_js(shapeIndex, &params)?, scale, precision, material_file)
+}
+
+// Exports the .mtl file that belongs to the OBJ export.
+#[wasm_bindgen]
+pub fn _export_material() -> String {
+    export_material()
+}
+
+// Exports the shape as binary or ASCII STL format.
+#[wasm_bindgen]
+pub fn _export_shape_stl(shapeIndex: usize, scale: Vec<f32>, params: JsValue, binary: bool) -> Result<Vec<u8>, ShapeError> {
+    export_shape_stl(&ShapeParams::from_js(shapeIndex, &params)?, scale, binary)
+}
+
+// Exports the shape as an embedded .gltf or a binary .glb file.
+#[wasm_bindgen]
+pub fn _export_shape_gltf(shapeIndex: usize, scale: Vec<f32>, params: JsValue, binary: bool) -> Result<Vec<u8>, ShapeError> {
+    export_shape_gltf(&ShapeParams::from_js(shapeIndex, &params)?, scale, binary)
+}
+
+// Exports the shape as an ASCII or binary PLY file with optional normals and vertex colors.
+#[wasm_bindgen]
+pub fn _export_shape_ply(
+    shapeIndex: usize,
+    scale: Vec<f32>,
+    params: JsValue,
+    binary: bool,
+    normals: bool,
+    color_scheme: ColorScheme,
+    color: Vec<u8>,
+) -> Result<Vec<u8>, ShapeError> {
+    export_shape_ply(&ShapeParams::from_js(shapeIndex, &params)?, scale, binary, normals, color_scheme, color)
+}
+
+// The most vertices a shape can have when the browser only supports 16 bit indices.
+const MAX_UINT16_VERTICES: usize = 1 << 16;
+
+// The webclient struct is responsible for drawing the generated geomtry on the html canvas. It uses a simple shader that lights the shape from its normals so that the user can differentiate the faces from each other.
 #[wasm_bindgen]
 pub struct WebClient {
-    nr_of_vertices: usize,
+    nr_of_indices: usize,
+    uint_indices: bool,
     
     // Html related fields
     gl: WebGlRenderingContext,
@@ -77,14 +169,18 @@ pub struct WebClient {
 impl WebClient {
     // To be able to use this struct we first need to initialize it.
     pub fn new() -> WebClient {
-        let nr_of_vertices = 0;
+        let nr_of_indices = 0;
         let gl = get_webgl_context().unwrap();
         let canvas = get_canvas().unwrap();
 
+        // WebGL 1 only supports 16 bit indices by default, which limits a mesh to 65536 vertices. This extension allows us to use 32 bit indices, without it we fall back to 16 bit indices.
+        let uint_indices = matches!(gl.get_extension("OES_element_index_uint"), Ok(Some(_)));
+
         // Vertex shader program
         let vsSource = 
         r#"
         attribute vec4 aVertexPosition;
+        attribute vec3 aVertexNormal;
         attribute vec3 aVertexColor;
         
         uniform mat4 uModelViewMatrix;
@@ -94,7 +190,13 @@ impl WebClient {
         
         void main(void) {
             gl_Position = uProjectionMatrix * uModelViewMatrix * aVertexPosition;
-            vColor = aVertexColor;
+
+            // A simple directional light from the top right of the camera, with some ambient light so the back stays visible.
+            vec3 normal = normalize((uModelViewMatrix * vec4(aVertexNormal, 0.0)).xyz);
+            vec3 lightDirection = normalize(vec3(0.5, 0.7, 1.0));
+            float light = 0.4 + 0.6 * max(dot(normal, lightDirection), 0.0);
+
+            vColor = aVertexColor * light;
         }
         "#;
         
@@ -133,17 +235,18 @@ impl WebClient {
 
         // Here we declare some refcell so we can acces this from different parts of our program.
         let drag = Rc::new(RefCell::new(false));
-        let theta = Rc::new(RefCell::new(-0.785398));
-        let phi = Rc::new(RefCell::new(0.392699));
+        let theta = Rc::new(RefCell::new(-FRAC_PI_4));
+        let phi = Rc::new(RefCell::new(FRAC_PI_8));
         let dX = Rc::new(RefCell::new(0.0));
         let dY = Rc::new(RefCell::new(0.0));
         let canvas_width = Rc::new(RefCell::new(canvas.client_width() as f32));
         let canvas_height = Rc::new(RefCell::new(canvas.client_height() as f32));
 
-        log(&"==== WebClient new() ====");
+        log("==== WebClient new() ====");
 
         WebClient { 
-            nr_of_vertices, 
+            nr_of_indices, 
+            uint_indices,
 
             gl,
             canvas,
@@ -162,45 +265,56 @@ impl WebClient {
         }
     }
 
-    // Here we generate the geometry and push it to de gpu and the shader.
+    // Here we generate the geometry and push it to de gpu and the shader. Invalid arguments are thrown as a ShapeError and leave the current shape on screen.
     pub fn generate(
         &mut self, 
         shape_index: usize, 
         scale: Vec<f32>, 
-        args: Vec<f32>
+        params: JsValue
     ) 
         -> Result<(), JsValue> 
         {
         // Creating the raw data we need.
-        let shape_data = generate_geometry(shape_index, scale, args);
-        let nr_of_triangles = shape_data.nr_of_triangles;
-        self.nr_of_vertices = nr_of_triangles * 3;
-        let vertices = shape_data.vertices;
+        let shape_data = generate_geometry(&ShapeParams::from_js(shape_index, &params)?, scale)?;
+        self.upload(shape_data)?;
+
+        log("==== WebClient generate() ====");
+
+        Ok(())
+    }
+
+    // Here we read an OBJ file and push it to the gpu and the shader in place of a generated shape. Errors in the file are thrown as an ObjError with the line number.
+    pub fn load_obj(&mut self, text: String) -> Result<(), JsValue> {
+        let mut shape_data = parse_obj(&text)?;
+        fit_in_view(&mut shape_data);
+        self.upload(shape_data)?;
+
+        log("==== WebClient load_obj() ====");
+
+        Ok(())
+    }
+
+    // Pushes the shape data to the gpu and the shader.
+    fn upload(&mut self, shape_data: ShapeData) -> Result<(), JsValue> {
+        let nr_of_vertices = shape_data.nr_of_vertices();
+
+        // Without 32 bit indices a larger shape can't be drawn, in which case the current shape stays on screen.
+        if !self.uint_indices && nr_of_vertices > MAX_UINT16_VERTICES {
+            return Err(ShapeError::TooManyVertices { vertices: nr_of_vertices, max: MAX_UINT16_VERTICES }.into());
+        }
+        self.nr_of_indices = shape_data.indices.len();
 
         // We also need to make a color array to tell webgl which color eacht vertice is.
         let mut colors: Vec<f32> = Vec::new();
         
-        // We switch between light and dark gray
-        for _ in (0..nr_of_triangles).step_by(2) {
-            let light_gray: Vec<f32> = vec![0.75294, 0.75294, 0.75294];
-            let dark_gray: Vec<f32> = vec![0.50196, 0.50196, 0.50196];
-            
-            colors.extend(&light_gray);
-            colors.extend(&light_gray);
-            colors.extend(&light_gray);
-            
-            colors.extend(&dark_gray);
-            colors.extend(&dark_gray);
-            colors.extend(&dark_gray);
+        // The vertices are shared between triangles, so the whole shape is light gray. The shader lights it from the normals, which keeps the faces distinguishable.
+        for _ in 0..nr_of_vertices {
+            colors.extend(LIGHT_GRAY.iter().map(|&c| c as f32 / 255.0));
         }
 
         // Here's where we call the routine that builds all the
         // Objects we'll be drawing.
-        self.init_buffers(vertices, colors).unwrap();
-
-        log(&"==== WebClient generate() ====");
-
-        Ok(())
+        self.init_buffers(shape_data.vertices, shape_data.normals, colors, shape_data.indices)
     }
 
     pub fn initCallBacks(&mut self) {        
@@ -253,11 +367,11 @@ impl WebClient {
                     *phi.borrow_mut() += *dY.borrow();
                     
                     // Numbers in radians.
-                    if *phi.borrow() > 1.5708 {
-                        *phi.borrow_mut() = 1.5708;
+                    if *phi.borrow() > FRAC_PI_2 {
+                        *phi.borrow_mut() = FRAC_PI_2;
                     }
-                    else if *phi.borrow() < -1.5708 {
-                        *phi.borrow_mut() = -1.5708;
+                    else if *phi.borrow() < -FRAC_PI_2 {
+                        *phi.borrow_mut() = -FRAC_PI_2;
                     }
                 }
             }) as Box<dyn FnMut(web_sys::MouseEvent)>);
@@ -267,14 +381,16 @@ impl WebClient {
             mousemove_cb.forget();
         }
 
-        log(&"==== WebClient initCallBacks() ====");
+        log("==== WebClient initCallBacks() ====");
     }
     
     #[allow(non_snake_case)]
     fn init_buffers(
         &self, 
         vertices: Vec<f32>, 
+        normals: Vec<f32>, 
         colors: Vec<f32>, 
+        indices: Vec<u32>,
     ) -> Result<(), JsValue> {
         // ==== VERTICES
         
@@ -319,6 +435,47 @@ impl WebClient {
             self.gl.enable_vertex_attrib_array(vertexPosition);
         }
         
+        // ==== NORMALS
+
+        // Create a buffer for the vertex normals.
+        let normalBuffer = self.gl
+            .create_buffer()
+            .ok_or("failed to create normalBuffer buffer")?;
+
+        // Select the normalBuffer as the one to apply buffer
+        // operations to from here out.
+        self.gl.bind_buffer(WebGlRenderingContext::ARRAY_BUFFER, Some(&normalBuffer));
+
+        let normals_array = float_32_array!(normals);
+
+        // Now pass the list of normals into WebGL. These are
+        // used by the shader to light the shape.
+        self.gl.buffer_data_with_array_buffer_view(
+            WebGlRenderingContext::ARRAY_BUFFER,
+            &normals_array,
+            WebGlRenderingContext::STATIC_DRAW,
+        );
+
+        // Tell WebGL how to pull out the normals from the normal buffer into the vertexNormal attribute
+        {
+            let vertexNormal = self.gl.get_attrib_location(&self.shaderProgram, "aVertexNormal") as u32;
+            let numComponents = 3;
+            let type_ = WebGlRenderingContext::FLOAT;
+            let normalize = false;
+            let stride = 0;
+            let offset = 0;
+            self.gl.bind_buffer(WebGlRenderingContext::ARRAY_BUFFER, Some(&normalBuffer));
+            self.gl.vertex_attrib_pointer_with_i32(
+                vertexNormal,
+                numComponents,
+                type_,
+                normalize,
+                stride,
+                offset,
+            );
+            self.gl.enable_vertex_attrib_array(vertexNormal);
+        }
+
         // ==== COLORS
         
         // Create a buffer for the color positions.
@@ -361,7 +518,38 @@ impl WebClient {
             self.gl.enable_vertex_attrib_array(vertexColor);
         }
 
-        log(&"==== WebClient initBuffers() ====");
+        // ==== INDICES
+
+        // Create a buffer for the indices.
+        let indexBuffer = self.gl
+            .create_buffer()
+            .ok_or("failed to create indexBuffer buffer")?;
+
+        // Select the indexBuffer as the one to apply buffer
+        // operations to from here out.
+        self.gl.bind_buffer(WebGlRenderingContext::ELEMENT_ARRAY_BUFFER, Some(&indexBuffer));
+
+        // Now pass the list of indices into WebGL. Every trio
+        // of indices tells WebGL which vertices form a triangle.
+        if self.uint_indices {
+            let indices_array = uint_32_array!(indices);
+            self.gl.buffer_data_with_array_buffer_view(
+                WebGlRenderingContext::ELEMENT_ARRAY_BUFFER,
+                &indices_array,
+                WebGlRenderingContext::STATIC_DRAW,
+            );
+        } else {
+            // upload() made sure every index fits in 16 bits.
+            let indices: Vec<u16> = indices.iter().map(|&index| index as u16).collect();
+            let indices_array = uint_16_array!(indices);
+            self.gl.buffer_data_with_array_buffer_view(
+                WebGlRenderingContext::ELEMENT_ARRAY_BUFFER,
+                &indices_array,
+                WebGlRenderingContext::STATIC_DRAW,
+            );
+        }
+
+        log("==== WebClient initBuffers() ====");
         
         Ok(())
     }
@@ -388,20 +576,20 @@ impl WebClient {
         
         // Now move the drawing position a bit to where we want to
         // start drawing the square.
-        let mat_to_translate = modelViewMatrix.clone();
+        let mat_to_translate = modelViewMatrix;
         mat4::translate(
             &mut modelViewMatrix, // destination matrix
             &mat_to_translate,    // matrix to translate
             &[-0.0, 0.0, -6.0],
         ); // amount to translate
         
-        let mat_to_rotate = modelViewMatrix.clone();
+        let mat_to_rotate = modelViewMatrix;
         mat4::rotate_x(
             &mut modelViewMatrix, // destination matrix
             &mat_to_rotate,       // matrix to rotate
             &*self.phi.borrow(),
         );
-        let mat_to_rotate = modelViewMatrix.clone();
+        let mat_to_rotate = modelViewMatrix;
         mat4::rotate_y(
             &mut modelViewMatrix, // destination matrix
             &mat_to_rotate,       // matrix to rotate
@@ -440,14 +628,21 @@ impl WebClient {
         );
         
         // Draw the triangles
-        self.gl.draw_arrays(
+        self.gl.draw_elements_with_i32(
             WebGlRenderingContext::TRIANGLES,
+            self.nr_of_indices as i32,
+            if self.uint_indices { WebGlRenderingContext::UNSIGNED_INT } else { WebGlRenderingContext::UNSIGNED_SHORT },
             0,
-            self.nr_of_vertices as i32,
         );
 
-        log(&"==== WebClient drawScene() ====");
+        log("==== WebClient drawScene() ====");
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+impl Default for WebClient {
+    fn default() -> Self {
+        WebClient::new()
+    }
+}
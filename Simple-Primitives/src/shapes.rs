@@ -4,35 +4,286 @@
     - Generate
     - Export
 
-    The generate function returns a ShapeData enum which holds all the vertices and the amount of triangles.
-    The export function returns a string which contains all shapedata in OBJ format.
+    The generate function returns a ShapeData struct which holds an indexed mesh: a list of shared vertices with their normals and UV coordinates and a list of indices where every trio of indices forms a triangle.
+    The export function returns a string which contains all shapedata in OBJ format, see obj.rs. The STL export function returns the shapedata as a binary or ASCII STL file, see stl.rs.
+    The glTF export function returns the shapedata as an embedded .gltf or a binary .glb file, see gltf.rs.
+    The PLY export function returns the shapedata as an ASCII or binary PLY file with optional normals and vertex colors, see ply.rs.
+    OBJ files can be read back into shapedata with parse_obj, see obj.rs.
+
+    Every shape in this file has a function that returns the ShapeData which makes up the shape.
+    Vertices are added with shape_data.push_vertex( ... ) which returns the index of the new vertex. These indices are then connected into triangles with shape_data.push_triangle( ... ).
+    Shapes that consist of multiple parts, like the top, side and bottom of a cylinder, call shape_data.begin_group( ... ) before each part so the parts can be exported as named groups.
+    Every vertex gets an analytic normal. Flat parts like the sides of the cube and the caps of the cylinder get the normal of the face, curved parts like the sphere and the cylinder wall get the normal of the surface at that vertex.
+
+    Every vertex also gets a UV coordinate. The UV layout differs per shape:
+    - Plane, Disk, Ring, Terrain - Planar projection over the whole texture.
+    - Cube, Sphere, Rounded Box - A cube cross of 4 by 3 cells. The sides around the Y axis form the middle row with the top above and the bottom below the front (+Z) side.
+    - UV Sphere             - Equirectangular, U runs around the Y axis and V from the bottom pole to the top pole.
+    - Icosphere             - The net of the icosahedron: a strip of 5 triangles on top, 10 in the middle and 5 at the bottom.
+    - Cylinder, Tube, Cone, Prism, Pyramid - The walls are wrapped around the top half of the texture, the caps are two separate circles in the bottom half.
+                              The cut faces of a slice share the squares of the caps, see cut_face().
+    - Capsule               - Like the UV sphere, with V stretched over the whole height.
+    - Platonic solids       - The net of the solid, except for the dodecahedron where every pentagon gets its own cell in a grid of 4 by 3.
+    - Torus                 - U runs around the Y axis and V around the tube, starting at the outside.
+    - Lathe                 - U runs around the Y axis like on the cylinder wall and V along the profile, spread by the length of the profile.
+    - Sweep                 - U runs around the section and V along the path in the top half of the texture, the caps are planar projections of the section in the two squares of the bottom half.
+    - Extrude               - The sides are wrapped around the top half of the texture like the cylinder wall, every outline and hole once. The front and the back are planar projections in the two squares of the bottom half.
+    Where a wrapped texture meets itself, like the seam of a cylinder wall, the vertices are duplicated so both sides of the seam get their own UV coordinate.
+
+    Every shape has it's own custom options. There are passed to generate_geometry as ShapeParams, which holds a variant with typed fields for every shape, see params.rs.
+    Smooth gives hard edges when it is false: every triangle gets its own vertices with the normal of the triangle.
+    Any shape can be displaced by seeded noise, which pushes its vertices along their normals to make rocks and blobs out of the basic shapes, see ShapeData::displace().
+    The parameters are checked before the shape is made, an invalid parameter is returned as a ShapeError, see error.rs.
+
+    The order of the indices is important as it is the definition of which way the face of the triangle is visible.
+
+  =================== */
 
-    Every shape in this file has two functions. One that returns the amount of triangles and the other one that returns a list with all the vertices which makes up the shapes.
-    In every second function you see groups of three temp_vec.push( ... ); lines. These push a single vertice in the temporary list. (x, y, z)
+use wasm_bindgen::prelude::*;
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+mod error;
+mod gltf;
+mod noise;
+mod obj;
+mod params;
+mod ply;
+mod stl;
+mod triangulate;
+pub use error::ShapeError;
+pub use gltf::{glb, gltf_embedded};
+pub use obj::{mtl, obj, parse_obj, ObjError, ObjOptions};
+pub use params::{Displacement, ParamInfo, ParamKind, ShapeParams, SphereMode, DEFAULT_HOLE, DEFAULT_OUTLINE, DEFAULT_PATH, DEFAULT_PROFILE};
+pub use ply::{ply_ascii, ply_binary};
+pub use stl::{stl_ascii, stl_binary};
+use noise::Noise;
+use std::collections::HashMap;
+
+// The colors the WebClient uses to tell the triangles apart.
+pub const LIGHT_GRAY: [u8; 3] = [192, 192, 192];
+pub const DARK_GRAY: [u8; 3] = [128, 128, 128];
+
+// WebGL needs the vertices and the indices that connect them into triangles so this struct is passed back to the webCLient struct.
+// Every trio of f32's in vertices and normals forms a vector3, every duo of f32's in uvs forms a vector2 and every trio of u32's in indices forms a triangle.
+#[derive(Debug, Clone)]
+pub struct ShapeData {
+    pub vertices: Vec<f32>,
+    pub normals: Vec<f32>,
+    pub uvs: Vec<f32>,
+    pub indices: Vec<u32>,
+    pub groups: Vec<Group>,
+}
 
-    Every shape has it's own custom options. There are passed to the shape functions via the args list. Each shape has the following options:
-    - Plane         - Subdivisions (usize)
-    - Disk          - Radius (f32), Sides (usize)
-    - Cube          - Subdivisions (usize)
-    - Sphere        - Subdivisions (usize)
-    - cylinder      - Radius (f32), Sides (usize)
-    - Tube          - Sides (usize), InnerRadius (f32), OuterRadius (f32)
+// A named part of a shape, like the top of a cylinder. The group holds count triangles starting at triangle start.
+#[derive(Debug, Clone)]
+pub struct Group {
+    pub name: String,
+    pub start: usize,
+    pub count: usize,
+}
 
-    The order of the vertices is important as it is the definition of which way the face of the triangle is visible.
+impl ShapeData {
+    pub fn new() -> ShapeData {
+        ShapeData {
+            vertices: Vec::new(),
+            normals: Vec::new(),
+            uvs: Vec::new(),
+            indices: Vec::new(),
+            groups: Vec::new(),
+        }
+    }
 
-  =================== */ 
+    // Returns the amount of unique vertices in the mesh.
+    pub fn nr_of_vertices(&self) -> usize {
+        self.vertices.len() / 3
+    }
 
-use wasm_bindgen::prelude::*;
-use std::f32::consts::PI;
+    // Returns the amount of triangles the mesh consists off.
+    pub fn nr_of_triangles(&self) -> usize {
+        self.indices.len() / 3
+    }
 
-// WebGL needs vertices and how many triangles it's going to draw so this struct is passed back to the webCLient struct.
-pub struct ShapeData {
-    pub vertices: Vec<f32>,
-    pub nr_of_triangles: usize,
+    // Adds a vertex with its normal and UV coordinate to the mesh and returns its index.
+    fn push_vertex(&mut self, position: [f32; 3], normal: [f32; 3], uv: [f32; 2]) -> u32 {
+        let index = self.nr_of_vertices() as u32;
+        self.vertices.extend(&position);
+        self.normals.extend(&normal);
+        self.uvs.extend(&uv);
+        index
+    }
+
+    // Adds a triangle to the mesh. The vertices have to be given in counter clockwise order when looking at the front of the triangle.
+    fn push_triangle(&mut self, a: u32, b: u32, c: u32) {
+        self.indices.push(a);
+        self.indices.push(b);
+        self.indices.push(c);
+
+        if let Some(group) = self.groups.last_mut() {
+            group.count += 1;
+        }
+    }
+
+    // Starts a new group, every triangle that is added after this belongs to it.
+    fn begin_group(&mut self, name: &str) {
+        self.groups.push(Group {
+            name: String::from(name),
+            start: self.nr_of_triangles(),
+            count: 0,
+        });
+    }
+
+    // Returns the position of the vertex at the given index.
+    fn position(&self, index: u32) -> [f32; 3] {
+        let i = index as usize * 3;
+        [self.vertices[i], self.vertices[i + 1], self.vertices[i + 2]]
+    }
+
+    // Returns the normal of the vertex at the given index.
+    fn normal(&self, index: u32) -> [f32; 3] {
+        let i = index as usize * 3;
+        [self.normals[i], self.normals[i + 1], self.normals[i + 2]]
+    }
+
+    // Returns the UV coordinate of the vertex at the given index.
+    fn uv(&self, index: u32) -> [f32; 2] {
+        let i = index as usize * 2;
+        [self.uvs[i], self.uvs[i + 1]]
+    }
+
+    // Returns a copy of the mesh where every triangle has its own three vertices with the normal of the triangle. This gives the mesh hard edges.
+    pub fn hard_edges(&self) -> ShapeData {
+        let mut shape_data = self.split_triangles();
+
+        for triangle in shape_data.indices.chunks(3) {
+            let normal = face_normal(
+                shape_data.position(triangle[0]),
+                shape_data.position(triangle[1]),
+                shape_data.position(triangle[2]),
+            );
+
+            for &index in triangle {
+                let i = index as usize * 3;
+                shape_data.normals[i..i + 3].copy_from_slice(&normal);
+            }
+        }
+
+        shape_data
+    }
+
+    // Returns a copy of the mesh where every triangle has its own three vertices, so vertex i belongs to triangle i / 3. The normals and UV coordinates are kept.
+    pub fn split_triangles(&self) -> ShapeData {
+        let mut shape_data = ShapeData::new();
+
+        for triangle in self.indices.chunks(3) {
+            let a = shape_data.push_vertex(self.position(triangle[0]), self.normal(triangle[0]), self.uv(triangle[0]));
+            let b = shape_data.push_vertex(self.position(triangle[1]), self.normal(triangle[1]), self.uv(triangle[1]));
+            let c = shape_data.push_vertex(self.position(triangle[2]), self.normal(triangle[2]), self.uv(triangle[2]));
+            shape_data.push_triangle(a, b, c);
+        }
+
+        // The triangles keep their order so the groups stay the same.
+        shape_data.groups = self.groups.clone();
+
+        shape_data
+    }
+
+    // Returns a copy of the mesh where every vertex is pushed along its normal by fractal noise of the seed, by at most the amplitude. The same seed always gives the same mesh.
+    // Vertices at the same position, like the seam of a sphere or the corner of a cube, move together along the average of their normals so the mesh doesn't tear open.
+    // The normals are then made again from the triangles around every vertex. Vertices that shared a position and a normal share the new normal as well, so hard edges stay hard and seams stay smooth.
+    pub fn displace(&self, displacement: &Displacement) -> ShapeData {
+        let mut shape_data = self.clone();
+        let noise = Noise::new(displacement.seed as u64);
+        let key = |vector: [f32; 3]| vector.map(|value| (value * 1e5).round() as i64);
+
+        let mut positions: HashMap<[i64; 3], [f32; 3]> = HashMap::new();
+        for i in 0..self.nr_of_vertices() as u32 {
+            let sum = positions.entry(key(self.position(i))).or_insert([0.0; 3]);
+            *sum = add(*sum, self.normal(i));
+        }
+        for i in 0..self.nr_of_vertices() as u32 {
+            let position = self.position(i);
+            let [x, y, z] = scale(position, displacement.frequency);
+            let offset = displacement.amplitude * noise.fractal_3d(x, y, z, displacement.octaves, 2.0, 0.5);
+            let moved = add(position, scale(normalize(positions[&key(position)]), offset));
+            shape_data.vertices[i as usize * 3..i as usize * 3 + 3].copy_from_slice(&moved);
+        }
+
+        // Every triangle adds its normal, weighted by its area, to its corners.
+        let mut normals: HashMap<([i64; 3], [i64; 3]), [f32; 3]> = HashMap::new();
+        let corner = |i: u32| (key(self.position(i)), key(self.normal(i)));
+        for triangle in shape_data.indices.chunks(3) {
+            let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|i| shape_data.position(i));
+            let area_normal = cross(sub(b, a), sub(c, a));
+            for &i in triangle {
+                let sum = normals.entry(corner(i)).or_insert([0.0; 3]);
+                *sum = add(*sum, area_normal);
+            }
+        }
+        for i in 0..self.nr_of_vertices() as u32 {
+            // A corner with only flat triangles around it keeps its old normal.
+            if let Some(&sum) = normals.get(&corner(i)).filter(|sum| dot(**sum, **sum) > 0.0) {
+                shape_data.normals[i as usize * 3..i as usize * 3 + 3].copy_from_slice(&normalize(sum));
+            }
+        }
+
+        shape_data
+    }
+}
+
+impl Default for ShapeData {
+    fn default() -> Self {
+        ShapeData::new()
+    }
+}
+
+// Returns the vector with a length of 1 that points in the same direction. A vector with a length of 0 is returned as is.
+fn normalize(vector: [f32; 3]) -> [f32; 3] {
+    let length = (
+        (vector[0] * vector[0]) +
+        (vector[1] * vector[1]) +
+        (vector[2] * vector[2])
+    ).sqrt();
+
+    if length == 0.0 {
+        return vector;
+    }
+
+    [vector[0] / length, vector[1] / length, vector[2] / length]
+}
+
+// Returns the normal of the triangle a, b, c. The normal points to the side where the vertices are in counter clockwise order.
+fn face_normal(a: [f32; 3], b: [f32; 3], c: [f32; 3]) -> [f32; 3] {
+    normalize(cross(sub(b, a), sub(c, a)))
+}
+
+// The vector math the shapes need.
+fn add(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn scale(vector: [f32; 3], factor: f32) -> [f32; 3] {
+    [vector[0] * factor, vector[1] * factor, vector[2] * factor]
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
 }
 
 // The shape enum.
 #[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Shape {
     Plane = 0,
     Disk = 1,
@@ -40,581 +291,1601 @@ pub enum Shape {
     Sphere = 3,
     Cylinder = 4,
     Tube = 5,
+    Cone = 6,
+    Torus = 7,
+    Capsule = 8,
+    Tetrahedron = 9,
+    Octahedron = 10,
+    Dodecahedron = 11,
+    Icosahedron = 12,
+    RoundedBox = 13,
+    Ring = 14,
+    Prism = 15,
+    Pyramid = 16,
+    Lathe = 17,
+    Extrude = 18,
+    Sweep = 19,
+    Terrain = 20,
+}
+
+impl Shape {
+    // Returns the name of the shape, which is used as the object name in exports.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Shape::Plane => "Plane",
+            Shape::Disk => "Disk",
+            Shape::Cube => "Cube",
+            Shape::Sphere => "Sphere",
+            Shape::Cylinder => "Cylinder",
+            Shape::Tube => "Tube",
+            Shape::Cone => "Cone",
+            Shape::Torus => "Torus",
+            Shape::Capsule => "Capsule",
+            Shape::Tetrahedron => "Tetrahedron",
+            Shape::Octahedron => "Octahedron",
+            Shape::Dodecahedron => "Dodecahedron",
+            Shape::Icosahedron => "Icosahedron",
+            Shape::RoundedBox => "Rounded Box",
+            Shape::Ring => "Ring",
+            Shape::Prism => "Prism",
+            Shape::Pyramid => "Pyramid",
+            Shape::Lathe => "Lathe",
+            Shape::Extrude => "Extrude",
+            Shape::Sweep => "Sweep",
+            Shape::Terrain => "Terrain",
+        }
+    }
 }
 
 // We export and generate function get the shape as an index but we want to be able to convert it to an enum.
 #[wasm_bindgen]
-pub fn usize2Shape(value: usize) -> Shape {
+pub fn usize2Shape(value: usize) -> Result<Shape, ShapeError> {
     match value {
-        0 => Shape::Plane,
-        1 => Shape::Disk,
-        2 => Shape::Cube,
-        3 => Shape::Sphere,
-        4 => Shape::Cylinder,
-        5 => Shape::Tube,
-        _ => panic!(),
+        0 => Ok(Shape::Plane),
+        1 => Ok(Shape::Disk),
+        2 => Ok(Shape::Cube),
+        3 => Ok(Shape::Sphere),
+        4 => Ok(Shape::Cylinder),
+        5 => Ok(Shape::Tube),
+        6 => Ok(Shape::Cone),
+        7 => Ok(Shape::Torus),
+        8 => Ok(Shape::Capsule),
+        9 => Ok(Shape::Tetrahedron),
+        10 => Ok(Shape::Octahedron),
+        11 => Ok(Shape::Dodecahedron),
+        12 => Ok(Shape::Icosahedron),
+        13 => Ok(Shape::RoundedBox),
+        14 => Ok(Shape::Ring),
+        15 => Ok(Shape::Prism),
+        16 => Ok(Shape::Pyramid),
+        17 => Ok(Shape::Lathe),
+        18 => Ok(Shape::Extrude),
+        19 => Ok(Shape::Sweep),
+        20 => Ok(Shape::Terrain),
+        _ => Err(ShapeError::UnknownShape(value)),
+    }
+}
+
+// The most vertices a generated shape can have, see ShapeParams::max_vertices().
+pub const MAX_VERTICES: usize = 1 << 21;
+
+// The most decimals the OBJ export writes, an f32 has no more significant digits than this.
+pub const MAX_PRECISION: usize = 9;
+
+// Returns the shape data as a string in OBJ format. Every value is written with the given amount of decimals, which can be at most MAX_PRECISION.
+// When material_file is not empty the OBJ file references it as its .mtl file, which can be made with export_material.
+pub fn export_shape(params: &ShapeParams, scale: Vec<f32>, precision: usize, material_file: String) -> Result<String, ShapeError> {
+    if precision > MAX_PRECISION {
+        return Err(ShapeError::InvalidCount { name: "precision", value: precision as f32, min: 0.0, max: MAX_PRECISION as f32 });
     }
+    let options = ObjOptions {
+        name: String::from(params.shape().name()),
+        precision,
+        material_file: if material_file.is_empty() { None } else { Some(material_file) },
+    };
+
+    Ok(obj(&generate_geometry(params, scale)?, &options))
 }
 
-// Returns the shape data as a string in OBJ format.
+// Returns the .mtl file that belongs to the OBJ export.
 #[wasm_bindgen]
-pub fn export_shape(shapeIndex: usize, scale: Vec<f32>, args: Vec<f32>) -> String {
-    let mut data = String::new();
+pub fn export_material() -> String {
+    mtl()
+}
 
-    let shape_data = generate_geometry(shapeIndex, scale, args);
+// Returns the shape data as an STL file. JS receives the bytes as an Uint8Array, which can be downloaded as is for both the binary and the ASCII version.
+pub fn export_shape_stl(params: &ShapeParams, scale: Vec<f32>, binary: bool) -> Result<Vec<u8>, ShapeError> {
+    let shape_data = generate_geometry(params, scale)?;
 
-    for i in (0..shape_data.vertices.len()).step_by(3) {
-        data.push_str("v ");
-        data.push_str(&shape_data.vertices[i    ].to_string());
-        data.push_str(" ");
-        data.push_str(&shape_data.vertices[i + 1].to_string());
-        data.push_str(" ");
-        data.push_str(&shape_data.vertices[i + 2].to_string());
-        data.push_str("\n");
+    if binary {
+        Ok(stl_binary(&shape_data))
+    } else {
+        Ok(stl_ascii(&shape_data, "simple_primitives").into_bytes())
     }
+}
 
-    for i in (0..(shape_data.vertices.len() as f32 / 3.0) as i32).step_by(3) {
-        data.push_str("f ");
-        data.push_str(&(i + 1).to_string());
-        data.push_str(" ");
-        data.push_str(&(i + 2).to_string());
-        data.push_str(" ");
-        data.push_str(&(i + 3).to_string());
-        data.push_str("\n");
+// Returns the shape data as a glTF file. JS receives the bytes as an Uint8Array, which holds a .glb file when binary is true and a .gltf file otherwise.
+pub fn export_shape_gltf(params: &ShapeParams, scale: Vec<f32>, binary: bool) -> Result<Vec<u8>, ShapeError> {
+    let shape_data = generate_geometry(params, scale)?;
+    let name = params.shape().name();
+
+    if binary {
+        glb(&shape_data, name)
+    } else {
+        Ok(gltf_embedded(&shape_data, name)?.into_bytes())
     }
+}
 
-    data.into()
+// The ways the PLY export can color the vertices.
+// - None           - No colors are written.
+// - Alternating    - The triangles switch between light and dark gray. This splits the triangles so every triangle has its own vertices.
+// - Solid          - Every vertex gets the color that is passed along.
+#[wasm_bindgen]
+pub enum ColorScheme {
+    None = 0,
+    Alternating = 1,
+    Solid = 2,
 }
 
-// Generate the geometry and returns the data as a "ShapeData" struct.
-pub fn generate_geometry(shape_index: usize, scale: Vec<f32>, args: Vec<f32>) -> ShapeData {
-    // Lets define the shape first so whe now what we need to generate. We also want to declare the variables that going to hold the definition of the shape.
-    let shape: Shape = usize2Shape(shape_index);
-    let mut vertices: Vec<f32> = Vec::new();
-    let mut nr_of_triangles = 0;
+// Returns the shape data as a PLY file. The color is only used by the solid color scheme and holds 3 u8's. JS receives the bytes as an Uint8Array.
+pub fn export_shape_ply(
+    params: &ShapeParams,
+    scale: Vec<f32>,
+    binary: bool,
+    normals: bool,
+    color_scheme: ColorScheme,
+    color: Vec<u8>,
+) -> Result<Vec<u8>, ShapeError> {
+    let mut shape_data = generate_geometry(params, scale)?;
+
+    let colors: Option<Vec<u8>> = match color_scheme {
+        ColorScheme::None => None,
+        ColorScheme::Alternating => {
+            shape_data = shape_data.split_triangles();
+            Some((0..shape_data.nr_of_vertices())
+                .flat_map(|i| if (i / 3) % 2 == 0 { LIGHT_GRAY } else { DARK_GRAY })
+                .collect())
+        }
+        ColorScheme::Solid => {
+            let color = color.get(0..3).ok_or(ShapeError::MissingArgument("color"))?;
+            Some(color.repeat(shape_data.nr_of_vertices()))
+        }
+    };
 
-    // Here we filter the selected shape and call the right functions to make the shape.
-    if let Shape::Plane = shape {
-        vertices = plane(args[0] as usize);
-        nr_of_triangles = nr_of_plane_triangles(args[0] as usize);
-    }
-    else if let Shape::Disk = shape {
-        vertices = disk(args[0] as usize, args[1] as f32);
-        nr_of_triangles = nr_of_disk_triangles(args[0] as usize);
+    if binary {
+        Ok(ply_binary(&shape_data, normals, colors.as_deref()))
+    } else {
+        Ok(ply_ascii(&shape_data, normals, colors.as_deref()).into_bytes())
     }
-    else if let Shape::Cube = shape {
-        vertices = cube(args[0] as usize);
-        nr_of_triangles = nr_of_cube_triangles(args[0] as usize);
+}
+
+// Generate the geometry and returns the data as a "ShapeData" struct, or the reason why the shape can't be made.
+pub fn generate_geometry(params: &ShapeParams, scale: Vec<f32>) -> Result<ShapeData, ShapeError> {
+    // The parameters can be made by hand in Rust, so we check them here as well.
+    params.validate()?;
+    if scale.len() < 3 {
+        return Err(ShapeError::MissingArgument("scale"));
     }
-    else if let Shape::Sphere = shape {
-        vertices = sphere(args[0] as usize);
-        nr_of_triangles = nr_of_sphere_triangles(args[0] as usize);
+    // A scale of 0 would flatten the shape and leave its normals undefined.
+    if let Some(&factor) = scale[..3].iter().find(|factor| !factor.is_finite() || **factor == 0.0) {
+        return Err(ShapeError::InvalidScale(factor));
     }
-    else if let Shape::Cylinder = shape {
-        vertices = cylinder(args[0] as usize, args[1] as f32);
-        nr_of_triangles = nr_of_cylinder_triangles(args[0] as usize);
+    // Counts can multiply each other, so a shape with every count near its max could run the wasm module out of memory.
+    let vertices = params.max_vertices();
+    if vertices > MAX_VERTICES as f64 {
+        return Err(ShapeError::TooManyVertices { vertices: vertices as usize, max: MAX_VERTICES });
     }
-    else if let Shape::Tube = shape {
-        vertices = tube(args[0] as usize, args[1] as f32, args[2] as f32);
-        nr_of_triangles = nr_of_tube_triangles(args[0] as usize);
+
+    // Here we filter the selected shape and call the right function to make the shape.
+    let (mut shape_data, smooth) = match *params {
+        // A displaced shape is made at its full scale first, so the noise is just as large in every direction.
+        ShapeParams::Displaced { ref base, ref displacement } => return Ok(generate_geometry(base, scale)?.displace(displacement)),
+        ShapeParams::Plane { subdivisions } => (plane(subdivisions), true),
+        ShapeParams::Terrain { ref heightmap, heightmap_width, x_subdivisions, y_subdivisions, height, seed, frequency, octaves, lacunarity, gain, smooth } => {
+            let noise = Noise::new(seed as u64);
+            let elevation = |x: f32, y: f32| {
+                if heightmap.is_empty() {
+                    noise.fractal_2d(x * frequency, y * frequency, octaves, lacunarity, gain) * 0.5 + 0.5
+                } else {
+                    sample_heightmap(heightmap, heightmap_width, x, y)
+                }
+            };
+            (terrain([x_subdivisions, y_subdivisions], height, elevation), smooth)
+        }
+        ShapeParams::Disk { sides, radius, cap_segments, start_angle, sweep_angle } => {
+            (disk("disk", sides, 0.0, radius, cap_segments, start_angle.to_radians(), sweep_angle.to_radians()), true)
+        }
+        ShapeParams::Ring { sides, inner_radius, outer_radius, cap_segments, start_angle, sweep_angle } => {
+            (disk("ring", sides, inner_radius, outer_radius, cap_segments, start_angle.to_radians(), sweep_angle.to_radians()), true)
+        }
+        ShapeParams::Cube { subdivisions } => (cube(subdivisions), true),
+        ShapeParams::Sphere { subdivisions, mode, smooth } => (sphere(subdivisions, mode), smooth),
+        ShapeParams::Cylinder { sides, radius, height_segments, cap_segments, start_angle, sweep_angle, side_caps, smooth } => {
+            (cylinder(sides, radius, height_segments, cap_segments, start_angle.to_radians(), sweep_angle.to_radians(), side_caps), smooth)
+        }
+        ShapeParams::Tube { sides, inner_radius, outer_radius, height_segments, cap_segments, start_angle, sweep_angle, side_caps, smooth } => {
+            let (start_angle, sweep_angle) = (start_angle.to_radians(), sweep_angle.to_radians());
+            (tube(sides, inner_radius, outer_radius, height_segments, cap_segments, start_angle, sweep_angle, side_caps), smooth)
+        }
+        ShapeParams::Cone { sides, bottom_radius, top_radius, height_segments, capped, smooth } => {
+            (cone(sides, bottom_radius, top_radius, height_segments, capped), smooth)
+        }
+        ShapeParams::Torus { major_radius, minor_radius, radial_segments, tubular_segments, arc, smooth } => {
+            (torus(major_radius, minor_radius, radial_segments, tubular_segments, arc.to_radians()), smooth)
+        }
+        ShapeParams::Capsule { radius, length, radial_segments, cap_rings, smooth } => {
+            (capsule(radius, length, radial_segments, cap_rings), smooth)
+        }
+        ShapeParams::Tetrahedron { subdivisions, spherical } => (platonic_solid("tetrahedron", &tetrahedron_faces(), subdivisions, spherical), true),
+        ShapeParams::Octahedron { subdivisions, spherical } => (platonic_solid("octahedron", &octahedron_faces(), subdivisions, spherical), true),
+        ShapeParams::Dodecahedron { subdivisions, spherical } => (platonic_solid("dodecahedron", &dodecahedron_faces(), subdivisions, spherical), true),
+        ShapeParams::Icosahedron { subdivisions, spherical } => (platonic_solid("icosahedron", &icosahedron_faces(), subdivisions, spherical), true),
+        // The sides of a prism and a pyramid are flat, so they always get hard edges.
+        ShapeParams::Prism { sides, radius, height, twist, height_segments } => (polygon_solid(sides, radius, radius, height, twist.to_radians(), height_segments), false),
+        ShapeParams::Pyramid { sides, radius, height, truncation } => {
+            // The pyramid is cut off parallel to its base, so the top shrinks along with the height that is left.
+            let top_radius = if height > 0.0 { radius * truncation / height } else { 0.0 };
+            (polygon_solid(sides, radius, top_radius, height - truncation, 0.0, 1), false)
+        }
+        ShapeParams::Lathe { ref profile, radial_segments, start_angle, sweep_angle, smooth } => {
+            (lathe(profile, radial_segments, start_angle.to_radians(), sweep_angle.to_radians()), smooth)
+        }
+        ShapeParams::Extrude { ref outline, ref holes, depth, bevel_size, bevel_segments } => (extrude(outline, holes, depth, bevel_size, bevel_segments), true),
+        ShapeParams::Sweep { ref path, ref section, ref scales, ref twists, sides, radius, path_segments, twist, capped, smooth } => {
+            // Without a section the sweep gets a circle, like the wall of a cylinder.
+            let section: Vec<[f32; 2]> = if section.is_empty() {
+                (0..sides).map(|k| 2.0 * PI * k as f32 / sides as f32).map(|angle| [radius * angle.cos(), radius * angle.sin()]).collect()
+            } else {
+                section.chunks(2).map(|corner| [corner[0], corner[1]]).collect()
+            };
+            let twists: Vec<f32> = twists.iter().map(|angle| angle.to_radians()).collect();
+            (sweep(path, &section, scales, &twists, path_segments, twist.to_radians(), capped), smooth)
+        }
+        ShapeParams::RoundedBox { width, height, depth, fillet_radius, fillet_segments } => {
+            let size = [width * scale[0].abs(), height * scale[1].abs(), depth * scale[2].abs()];
+            (rounded_box(size, fillet_radius, fillet_segments), true)
+        }
+    };
+
+    // The rounded box is made at its scaled size so the fillets stay round instead of being stretched, only the direction of the scale is left to apply.
+    let scale = match params {
+        ShapeParams::RoundedBox { .. } => scale.iter().map(|factor| factor.signum()).collect(),
+        _ => scale,
+    };
+
+    if !smooth {
+        shape_data = shape_data.hard_edges();
     }
 
     // This is the scale part. We want to check first if we actually need to apply scaling if the set scale differs from x: 1, y: 1, z: 1
     if !((scale[0] == 1.0) && (scale[1] == 1.0) && (scale[2] == 1.0)) {
         // If the user wants a different scale we simply multiply the scale vector with the vertice component wise.
-        for i in (0..vertices.len()).step_by(3) {
-            vertices[i    ] *= scale[0];
-            vertices[i + 1] *= scale[1];
-            vertices[i + 2] *= scale[2];
+        for vertex in shape_data.vertices.chunks_mut(3) {
+            vertex[0] *= scale[0];
+            vertex[1] *= scale[1];
+            vertex[2] *= scale[2];
+        }
+
+        // Normals need to be divided by the scale instead, otherwise they would lean towards the stretched axis. Afterwards they need to be normalized again.
+        for normal in shape_data.normals.chunks_mut(3) {
+            let scaled = normalize([normal[0] / scale[0], normal[1] / scale[1], normal[2] / scale[2]]);
+            normal.copy_from_slice(&scaled);
+        }
+
+        // A negative scale mirrors the shape, which turns the triangles inside out. Mirroring twice turns them back.
+        if scale[0] * scale[1] * scale[2] < 0.0 {
+            for triangle in shape_data.indices.chunks_mut(3) {
+                triangle.swap(1, 2);
+            }
         }
     }
 
     // Return all the info the webclient struct needs.
-    ShapeData {
-        vertices,
-        nr_of_triangles,
+    Ok(shape_data)
+}
+
+// ==== GRID
+
+// Adds a grid of (subdivisions[0] + 1) * (subdivisions[1] + 1) vertices to the shape data, with subdivisions[0] quads along the u axis and subdivisions[1] along the v axis. The position and uv functions map the grid coordinates, which run from -0.5 to 0.5, to a vertex.
+// The triangles face the side where the u axis turns counter clockwise into the v axis, so the caller decides which way the grid faces and passes the matching normal.
+fn grid<F, G>(shape_data: &mut ShapeData, subdivisions: [usize; 2], normal: [f32; 3], position: F, uv: G)
+where
+    F: Fn(f32, f32) -> [f32; 3],
+    G: Fn(f32, f32) -> [f32; 2],
+{
+    let [columns, rows] = subdivisions;
+    let first = shape_data.nr_of_vertices() as u32;
+
+    for y in 0..=rows {
+        for x in 0..=columns {
+            let u = x as f32 / columns as f32 - 0.5;
+            let v = y as f32 / rows as f32 - 0.5;
+            shape_data.push_vertex(position(u, v), normal, uv(u, v));
+        }
+    }
+
+    // In each iteration a quad is added to the list.
+    let columns = columns as u32;
+    for y in 0..rows as u32 {
+        for x in 0..columns {
+            let bottom_left = first + y * (columns + 1) + x;
+            let bottom_right = bottom_left + 1;
+            let top_left = bottom_left + columns + 1;
+            let top_right = top_left + 1;
+
+            shape_data.push_triangle(top_left, bottom_left, top_right);
+            shape_data.push_triangle(top_right, bottom_left, bottom_right);
+        }
     }
 }
 
 // ==== PLANE
 
-// Returns the amount of triangles the wanted plane consists off.
-fn nr_of_plane_triangles(subdivisions: usize) -> usize {
-    return (subdivisions * subdivisions) * 2;
+// Returns the ShapeData of a plane in the XY plane facing the positive Z axis.
+fn plane(subdivisions: usize) -> ShapeData {
+    let mut shape_data = ShapeData::new();
+
+    shape_data.begin_group("plane");
+    grid(&mut shape_data, [subdivisions; 2], [0.0, 0.0, 1.0], |u, v| [u, v, 0.0], |u, v| [u + 0.5, v + 0.5]);
+
+    shape_data
 }
 
-// Returns a Vec<f32> with all the vertices. Every trio of f32's forms a vector3.
-fn plane(subdivisions: usize) -> Vec<f32> {
-    let mut temp_vec: Vec<f32> = Vec::new();
-    let step = 1.0 / subdivisions as f32;
+// ==== TERRAIN
+
+// Returns the ShapeData of the plane with every vertex pushed up along the Z axis by the height times the elevation, which gets the position on the plane from 0 to 1 on both axes and returns a value from 0 to 1.
+// The normals follow the slope of the elevation between the neighbouring vertices, so the terrain is smooth.
+fn terrain<F>(subdivisions: [usize; 2], height: f32, elevation: F) -> ShapeData
+where
+    F: Fn(f32, f32) -> f32,
+{
+    let mut shape_data = ShapeData::new();
+
+    shape_data.begin_group("terrain");
+    grid(&mut shape_data, subdivisions, [0.0, 0.0, 1.0], |u, v| [u, v, height * elevation(u + 0.5, v + 0.5)], |u, v| [u + 0.5, v + 0.5]);
+
+    // The slope is measured over the vertices on both sides, or only the inner side at the border.
+    let slope = |x: f32, y: f32, step: [f32; 2]| {
+        let (left, right) = ((x - step[0]).max(0.0), (x + step[0]).min(1.0));
+        let (below, above) = ((y - step[1]).max(0.0), (y + step[1]).min(1.0));
+        [
+            height * (elevation(right, y) - elevation(left, y)) / (right - left),
+            height * (elevation(x, above) - elevation(x, below)) / (above - below),
+        ]
+    };
+    let step = [1.0 / subdivisions[0] as f32, 1.0 / subdivisions[1] as f32];
+    for (vertex, normal) in shape_data.vertices.chunks(3).zip(shape_data.normals.chunks_mut(3)) {
+        let [dx, dy] = slope(vertex[0] + 0.5, vertex[1] + 0.5, step);
+        normal.copy_from_slice(&normalize([-dx, -dy, 1.0]));
+    }
 
-    // In each iteration a quad is added to the list.
-    for y in 0..subdivisions {
-        for x in 0..subdivisions {
-            temp_vec.push(step * x as f32 - 0.5);
-            temp_vec.push(step * y as f32 - 0.5 + step);
-            temp_vec.push(0.0);
+    shape_data
+}
+
+// Returns the elevation of the heightmap at the position from 0 to 1 on both axes, blended between the 4 pixels around it. The first row of the heightmap is the top of the terrain.
+fn sample_heightmap(heightmap: &[u8], width: usize, x: f32, y: f32) -> f32 {
+    let rows = heightmap.len() / width;
+    let pixel = |column: usize, row: usize| heightmap[row * width + column] as f32 / 255.0;
 
-            temp_vec.push(step * x as f32 - 0.5);
-            temp_vec.push(step * y as f32 - 0.5);
-            temp_vec.push(0.0);
+    let (column, row) = (x * (width - 1) as f32, (1.0 - y) * (rows - 1) as f32);
+    let (left, top) = (column.floor() as usize, row.floor() as usize);
+    let (right, bottom) = ((left + 1).min(width - 1), (top + 1).min(rows - 1));
+    let (s, t) = (column - left as f32, row - top as f32);
 
-            temp_vec.push(step * x as f32 - 0.5 + step);
-            temp_vec.push(step * y as f32 - 0.5 + step);
-            temp_vec.push(0.0);
+    let upper = pixel(left, top) + (pixel(right, top) - pixel(left, top)) * s;
+    let lower = pixel(left, bottom) + (pixel(right, bottom) - pixel(left, bottom)) * s;
+    upper + (lower - upper) * t
+}
 
-            temp_vec.push(step * x as f32 - 0.5 + step);
-            temp_vec.push(step * y as f32 - 0.5 + step);
-            temp_vec.push(0.0);
+// ==== DISK
 
-            temp_vec.push(step * x as f32 - 0.5);
-            temp_vec.push(step * y as f32 - 0.5);
-            temp_vec.push(0.0);
+// Returns the ShapeData of a disk in the XY plane facing the positive Z axis, split into cap_segments rings around the center. An inner radius above 0 leaves a hole in the center, which gives a flat ring.
+// The disk runs counter clockwise from the start angle over the sweep angle, starting at the X axis. A sweep below 2 PI gives a slice, which needs a vertex at both ends of every ring instead of one shared vertex.
+#[allow(clippy::too_many_arguments)]
+fn disk(name: &str, sides: usize, inner_radius: f32, outer_radius: f32, cap_segments: usize, start_angle: f32, sweep_angle: f32) -> ShapeData {
+    let mut shape_data = ShapeData::new();
+    let step = sweep_angle / sides as f32;
+    let corners = if sweep_angle < 2.0 * PI { sides + 1 } else { sides };
+    let inner_scale = if outer_radius > 0.0 { inner_radius / outer_radius } else { 0.0 };
+
+    shape_data.begin_group(name);
+    let mut previous = None;
+    let center = (inner_radius == 0.0).then(|| shape_data.push_vertex([0.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.5, 0.5]));
+
+    // The disk is projected on the texture so the outer edge of the disk touches the edges of the texture.
+    for k in if center.is_some() { 1 } else { 0 }..=cap_segments {
+        let t = k as f32 / cap_segments as f32;
+        let radius = inner_radius + (outer_radius - inner_radius) * t;
+        let scale = inner_scale + (1.0 - inner_scale) * t;
+        let first = shape_data.nr_of_vertices() as u32;
+        for i in 0..corners {
+            let (sin, cos) = (start_angle + i as f32 * step).sin_cos();
+            shape_data.push_vertex(
+                [cos * radius, sin * radius, 0.0],
+                [0.0, 0.0, 1.0],
+                [0.5 + cos * scale * 0.5, 0.5 + sin * scale * 0.5],
+            );
+        }
 
-            temp_vec.push(step * x as f32 - 0.5 + step);
-            temp_vec.push(step * y as f32 - 0.5);
-            temp_vec.push(0.0);
+        // The first ring around the center is connected to the center, every other ring to the ring inside it.
+        for i in 0..sides as u32 {
+            let next = (i + 1) % corners as u32;
+            match (previous, center) {
+                (Some(inner), _) => {
+                    shape_data.push_triangle(inner + i, first + i, first + next);
+                    shape_data.push_triangle(inner + i, first + next, inner + next);
+                }
+                (None, Some(center)) => shape_data.push_triangle(first + i, first + next, center),
+                (None, None) => {}
+            }
         }
+        previous = Some(first);
     }
 
-    return temp_vec;
+    shape_data
 }
 
-// ==== DISK
+// ==== CUBE
+
+// Returns the UV coordinate of a point on one cell of the cube cross. The cross is 4 cells wide and 3 cells high, s and t run from 0 to 1 over the cell.
+fn cross_uv(column: f32, row: f32, s: f32, t: f32) -> [f32; 2] {
+    [(column + s) / 4.0, (row + t) / 3.0]
+}
 
-// Returns the amount of triangles the wanted disk consists off.
-fn nr_of_disk_triangles(sides: usize) -> usize {
-    return sides;    
+// Returns the ShapeData of a cube where every side is a subdivided plane. Every side has its own vertices so the sides don't share their edges.
+fn cube(subdivisions: usize) -> ShapeData {
+    let mut shape_data = ShapeData::new();
+
+    // For each side we generate a plane, so six times in total. The grid coordinates are swapped and mirrored so each side faces outwards.
+    // The UV coordinates are rotated per side so the texture is upright on the sides around the Y axis and continues over the edges to the top and bottom.
+    shape_data.begin_group("front");
+    grid(&mut shape_data, [subdivisions; 2], [ 0.0,  0.0,  1.0], |u, v| [ u,    v,    0.5], |u, v| cross_uv(1.0, 1.0, u + 0.5, v + 0.5));
+    shape_data.begin_group("back");
+    grid(&mut shape_data, [subdivisions; 2], [ 0.0,  0.0, -1.0], |u, v| [-u,    v,   -0.5], |u, v| cross_uv(3.0, 1.0, u + 0.5, v + 0.5));
+    shape_data.begin_group("top");
+    grid(&mut shape_data, [subdivisions; 2], [ 0.0,  1.0,  0.0], |u, v| [ v,    0.5,  u  ], |u, v| cross_uv(1.0, 2.0, v + 0.5, 0.5 - u));
+    shape_data.begin_group("bottom");
+    grid(&mut shape_data, [subdivisions; 2], [ 0.0, -1.0,  0.0], |u, v| [ u,   -0.5,  v  ], |u, v| cross_uv(1.0, 0.0, u + 0.5, v + 0.5));
+    shape_data.begin_group("right");
+    grid(&mut shape_data, [subdivisions; 2], [ 1.0,  0.0,  0.0], |u, v| [ 0.5,  u,    v  ], |u, v| cross_uv(2.0, 1.0, 0.5 - v, u + 0.5));
+    shape_data.begin_group("left");
+    grid(&mut shape_data, [subdivisions; 2], [-1.0,  0.0,  0.0], |u, v| [-0.5,  v,    u  ], |u, v| cross_uv(0.0, 1.0, u + 0.5, v + 0.5));
+
+    shape_data
 }
 
-// Returns a Vec<f32> with all the vertices. Every trio of f32's forms a vector3.
-fn disk(sides: usize, radius: f32) -> Vec<f32> {
-    let mut temp_vec: Vec<f32> = Vec::new();
-    let step = 2.0 * PI / sides as f32;
+// ==== ROUNDED BOX
+
+// A side of the rounded box: the name of the group, the u, v and normal axis of its grid as the index of the coordinate and the direction along it, and the function that maps the fraction along u and v to a UV coordinate.
+type BoxSide = (&'static str, (usize, f32), (usize, f32), (usize, f32), fn(f32, f32) -> [f32; 2]);
+
+// Returns the ShapeData of a box with the given size where the edges and corners are rounded with the fillet radius. The radius is limited to half of the smallest side, at which point the box turns into a capsule or a sphere.
+// Like the cube sphere, every side of the box is a grid whose vertices are pushed outwards from an inner box that is the fillet radius smaller on every side. A vertex of the grid that lies beyond the inner box is placed at the fillet radius from the nearest point of the inner box, which rounds the edges and corners.
+// Each side holds the half of the fillets that face it, so two sides meet where the fillet turns 45 degrees.
+fn rounded_box(size: [f32; 3], fillet_radius: f32, fillet_segments: usize) -> ShapeData {
+    let mut shape_data = ShapeData::new();
+    let radius = fillet_radius.min(size[0] / 2.0).min(size[1] / 2.0).min(size[2] / 2.0);
+    let inner = size.map(|side| side / 2.0 - radius);
+    let steps = inner.map(|inner| fillet_steps(inner, radius, fillet_segments));
+
+    // The sides are laid out the same way as the sides of the cube, so u turns counter clockwise into v when seen from the outside and every side lands on the same cell of the cube cross.
+    let sides: [BoxSide; 6] = [
+        ("front",  (0,  1.0), (1, 1.0), (2,  1.0), |u, v| cross_uv(1.0, 1.0, u, v)),
+        ("back",   (0, -1.0), (1, 1.0), (2, -1.0), |u, v| cross_uv(3.0, 1.0, u, v)),
+        ("top",    (2,  1.0), (0, 1.0), (1,  1.0), |u, v| cross_uv(1.0, 2.0, v, 1.0 - u)),
+        ("bottom", (0,  1.0), (2, 1.0), (1, -1.0), |u, v| cross_uv(1.0, 0.0, u, v)),
+        ("right",  (1,  1.0), (2, 1.0), (0,  1.0), |u, v| cross_uv(2.0, 1.0, 1.0 - v, u)),
+        ("left",   (2,  1.0), (1, 1.0), (0, -1.0), |u, v| cross_uv(0.0, 1.0, u, v)),
+    ];
+
+    for (name, (u_axis, u_direction), (v_axis, v_direction), (normal_axis, normal_direction), uv) in sides {
+        shape_data.begin_group(name);
+        let first = shape_data.nr_of_vertices() as u32;
+        let (u_steps, v_steps) = (&steps[u_axis], &steps[v_axis]);
+
+        for &(v, v_fraction) in v_steps {
+            for &(u, u_fraction) in u_steps {
+                // The point on the box that is the fillet radius larger than the inner box, which is then pulled onto the rounded surface.
+                let mut point = [0.0; 3];
+                point[u_axis] = u * u_direction;
+                point[v_axis] = v * v_direction;
+                point[normal_axis] = (inner[normal_axis] + radius) * normal_direction;
+
+                let nearest = [0, 1, 2].map(|k| point[k].clamp(-inner[k], inner[k]));
+                let mut normal = normalize(sub(point, nearest));
+                if radius == 0.0 {
+                    normal = [0.0; 3];
+                    normal[normal_axis] = normal_direction;
+                }
+
+                let position = [0, 1, 2].map(|k| nearest[k] + normal[k] * radius);
+                shape_data.push_vertex(position, normal, uv(u_fraction, v_fraction));
+            }
+        }
 
-    for i in 0..sides {
-        temp_vec.push((i       as f32 * step).cos() * radius);
-        temp_vec.push((i       as f32 * step).sin() * radius);
-        temp_vec.push(0.0                                   );
-            
-        temp_vec.push(((i + 1) as f32 * step).cos() * radius);
-        temp_vec.push(((i + 1) as f32 * step).sin() * radius);
-        temp_vec.push(0.0                                   );
-            
-        temp_vec.push(0.0                                   );
-        temp_vec.push(0.0                                   );
-        temp_vec.push(0.0                                   );
+        let columns = u_steps.len() as u32;
+        for y in 0..v_steps.len() as u32 - 1 {
+            for x in 0..columns - 1 {
+                let bottom_left = first + y * columns + x;
+                let bottom_right = bottom_left + 1;
+                let top_left = bottom_left + columns;
+                let top_right = top_left + 1;
+
+                shape_data.push_triangle(top_left, bottom_left, top_right);
+                shape_data.push_triangle(top_right, bottom_left, bottom_right);
+            }
+        }
     }
 
-    return temp_vec;
+    shape_data
 }
 
-// ==== CUBE
+// Returns the coordinates of the grid lines along one axis of the rounded box, from the negative to the positive side, together with how far along the rounded surface they lie as a fraction from 0 to 1 for the UV coordinates.
+// The coordinates lie on the box that is the fillet radius larger than the inner box. The lines on the fillets are spread so the fillet is split into equal angles, with fillet_segments parts per 90 degrees. When that number is odd the part in the middle is split in two, one half for each side.
+fn fillet_steps(inner: f32, radius: f32, fillet_segments: usize) -> Vec<(f32, f32)> {
+    let step = FRAC_PI_2 / fillet_segments as f32;
+    let mut angles: Vec<f32> = (0..fillet_segments).map(|i| i as f32 * step).take_while(|&angle| angle < FRAC_PI_4 - 1e-6).collect();
+    angles.push(FRAC_PI_4);
+
+    // The coordinates from the center outwards. When the side has no flat part the lines at -inner and inner fall together in the center, so that line is only added once.
+    let mut half: Vec<f32> = angles.iter().map(|angle| inner + radius * angle.tan()).collect();
+    half.dedup();
+    let mut coordinates: Vec<f32> = half.iter().rev().map(|coordinate| -coordinate).collect();
+    let center = if half[0] == 0.0 { 1 } else { 0 };
+    coordinates.extend(&half[center..]);
+
+    // The distance along the surface from the center of the side, the flat part first and then the arc of the fillet.
+    let arc = |coordinate: f32| {
+        let flat = coordinate.abs().min(inner);
+        let angle = if radius > 0.0 { ((coordinate.abs() - inner).max(0.0) / radius).atan() } else { 0.0 };
+        (flat + radius * angle).copysign(coordinate)
+    };
+    let length = 2.0 * (inner + radius * FRAC_PI_4);
+
+    coordinates.iter().map(|&coordinate| (coordinate, if length > 0.0 { 0.5 + arc(coordinate) / length } else { 0.5 })).collect()
+}
+
+// ==== SPHERE
 
-// Returns the amount of triangles the wanted cube consists off.
-fn nr_of_cube_triangles(subdivisions: usize) -> usize {
-    return nr_of_plane_triangles(subdivisions) * 6;
+// Returns the ShapeData of a sphere with a radius of 1. The mode decides how the sphere is built:
+// - Cube   - A normalized cube, with subdivisions per side of the cube.
+// - Uv     - Rings of latitude and longitude, with 2 * subdivisions rings and 4 * subdivisions sides so the equator has as many edges as the cube sphere.
+// - Ico    - A normalized icosahedron where every edge is split into subdivisions parts, which gives triangles of nearly the same size.
+fn sphere(subdivisions: usize, mode: SphereMode) -> ShapeData {
+    let mut shape_data = match mode {
+        SphereMode::Cube => cube_sphere(subdivisions),
+        SphereMode::Uv => uv_sphere(subdivisions * 2, subdivisions * 4),
+        SphereMode::Ico => icosphere(subdivisions),
+    };
+
+    // The parts blend into one round surface, so the sphere is a single group.
+    shape_data.groups = vec![Group {
+        name: String::from("sphere"),
+        start: 0,
+        count: shape_data.nr_of_triangles(),
+    }];
+
+    shape_data
 }
 
-// Returns a Vec<f32> with all the vertices. Every trio of f32's forms a vector3.
-fn cube(subdivisions: usize) -> Vec<f32> {
-    let mut temp_vec: Vec<f32> = Vec::new();
-    let step = 1.0 / subdivisions as f32;
+// Returns the ShapeData of a normalized cube. The UV coordinates of the cube are kept, which gives the sphere a cube map style UV layout.
+fn cube_sphere(subdivisions: usize) -> ShapeData {
+    let mut shape_data = cube(subdivisions);
 
-    // For each side we generat a plane, so six times in total.
+    // To generate a sphere we simply normalize all the vertices of a cube. Length = Sqrt(x^2 + y^2 + z^2) -> x / Length, y / Length, z / Length
+    // On a sphere with a radius of 1 the normal is the same as the position.
+    for (vertex, normal) in shape_data.vertices.chunks_mut(3).zip(shape_data.normals.chunks_mut(3)) {
+        let normalized = normalize([vertex[0], vertex[1], vertex[2]]);
+        vertex.copy_from_slice(&normalized);
+        normal.copy_from_slice(&normalized);
+    }
+
+    shape_data
+}
+
+// Returns the ShapeData of a sphere made of rings from the bottom pole to the top pole.
+fn uv_sphere(rings: usize, sides: usize) -> ShapeData {
+    let mut shape_data = ShapeData::new();
 
-    for y in 0..subdivisions {
-        for x in 0..subdivisions {            
-            temp_vec.push(step * x as f32 - 0.5);
-            temp_vec.push(step * y as f32 - 0.5 + step);
-            temp_vec.push(0.5);
+    let profile: Vec<ProfilePoint> = (0..=rings)
+        .map(|r| {
+            let v = r as f32 / rings as f32;
+            let (sin, cos) = (PI * (v - 0.5)).sin_cos();
 
-            temp_vec.push(step * x as f32 - 0.5);
-            temp_vec.push(step * y as f32 - 0.5);
-            temp_vec.push(0.5);
+            // The cosine of PI / 2 is a tiny negative number in f32, but the rings at the poles need a radius of exactly 0.
+            ProfilePoint { radius: cos.max(0.0), y: sin, normal: [cos, sin], v }
+        })
+        .collect();
+    revolve(&mut shape_data, sides, &profile);
 
-            temp_vec.push(step * x as f32 - 0.5 + step);
-            temp_vec.push(step * y as f32 - 0.5 + step);
-            temp_vec.push(0.5);
-            
-            temp_vec.push(step * x as f32 - 0.5 + step);
-            temp_vec.push(step * y as f32 - 0.5 + step);
-            temp_vec.push(0.5);
+    shape_data
+}
 
-            temp_vec.push(step * x as f32 - 0.5);
-            temp_vec.push(step * y as f32 - 0.5);
-            temp_vec.push(0.5);
+// Adds a triangle where every edge is split into subdivisions parts, so it consists of subdivisions * subdivisions triangles with the same winding as a, b, c.
+// The UV coordinates are interpolated between the UV coordinates of the corners. When spherical is true the vertices are pushed onto the sphere with a radius of 1 and get smooth normals, otherwise every vertex gets the normal of the triangle.
+fn subdivided_triangle(shape_data: &mut ShapeData, corners: [[f32; 3]; 3], uvs: [[f32; 2]; 3], subdivisions: usize, spherical: bool) {
+    let [a, b, c] = corners;
+    let flat_normal = face_normal(a, b, c);
+    let first = shape_data.nr_of_vertices() as u32;
+
+    // The vertices are added in rows from the a, b edge to the c corner. Row j holds subdivisions - j + 1 vertices.
+    for j in 0..=subdivisions {
+        for i in 0..=(subdivisions - j) {
+            let (s, t) = (i as f32 / subdivisions as f32, j as f32 / subdivisions as f32);
+            let mix = |p: [f32; 3], q: [f32; 3], r: [f32; 3], k: usize| p[k] + (q[k] - p[k]) * s + (r[k] - p[k]) * t;
+            let position = [mix(a, b, c, 0), mix(a, b, c, 1), mix(a, b, c, 2)];
+            let uv = [
+                uvs[0][0] + (uvs[1][0] - uvs[0][0]) * s + (uvs[2][0] - uvs[0][0]) * t,
+                uvs[0][1] + (uvs[1][1] - uvs[0][1]) * s + (uvs[2][1] - uvs[0][1]) * t,
+            ];
+
+            if spherical {
+                let position = normalize(position);
+                shape_data.push_vertex(position, position, uv);
+            } else {
+                shape_data.push_vertex(position, flat_normal, uv);
+            }
+        }
+    }
 
-            temp_vec.push(step * x as f32 - 0.5 + step);
-            temp_vec.push(step * y as f32 - 0.5);
-            temp_vec.push(0.5);
+    // The index of vertex i in row j.
+    let index = |i: usize, j: usize| first + (j * (2 * subdivisions + 3 - j) / 2 + i) as u32;
+    for j in 0..subdivisions {
+        for i in 0..(subdivisions - j) {
+            shape_data.push_triangle(index(i, j), index(i + 1, j), index(i, j + 1));
+            if i + 1 < subdivisions - j {
+                shape_data.push_triangle(index(i + 1, j), index(i + 1, j + 1), index(i, j + 1));
+            }
         }
     }
+}
 
-    for y in 0..subdivisions {
-        for x in 0..subdivisions {
-            temp_vec.push(step * x as f32 - 0.5);
-            temp_vec.push(step * y as f32 - 0.5);
-            temp_vec.push(-0.5);
+// Returns the ShapeData of an icosahedron pushed onto the sphere, see subdivided_triangle.
+fn icosphere(subdivisions: usize) -> ShapeData {
+    platonic_solid("sphere", &icosahedron_faces(), subdivisions, true)
+}
+
+// ==== CYLINDER
+
+// Adds a ring of sides + 1 vertices around the Y axis at the given height and returns the index of the first one. The last vertex lies on top of the first one so the seam can have its own UV coordinate.
+// The normal is given as [outwards, up], so [0.0, 1.0] points up for a cap and [1.0, 0.0] points away from the Y axis for a wall.
+// The uv function gets how far around the ring the vertex is, from 0 to 1, and the cosine and sine of its angle.
+fn ring<F>(shape_data: &mut ShapeData, sides: usize, radius: f32, y: f32, normal: [f32; 2], uv: F) -> u32
+where
+    F: Fn(f32, f32, f32) -> [f32; 2],
+{
+    arc_ring(shape_data, sides, 0.0, 2.0 * PI, radius, y, normal, uv)
+}
 
-            temp_vec.push(step * x as f32 - 0.5);
-            temp_vec.push(step * y as f32 - 0.5 + step);
-            temp_vec.push(-0.5);
+// Adds the part of a ring from the start angle over the sweep angle, see ring(). The angle starts at the X axis and turns towards the Z axis, which is clockwise when seen from above.
+#[allow(clippy::too_many_arguments)]
+fn arc_ring<F>(shape_data: &mut ShapeData, sides: usize, start_angle: f32, sweep_angle: f32, radius: f32, y: f32, normal: [f32; 2], uv: F) -> u32
+where
+    F: Fn(f32, f32, f32) -> [f32; 2],
+{
+    let step = sweep_angle / sides as f32;
+    let first = shape_data.nr_of_vertices() as u32;
+
+    for i in 0..=sides {
+        let (sin, cos) = (start_angle + i as f32 * step).sin_cos();
+        shape_data.push_vertex(
+            [cos * radius, y, sin * radius],
+            [cos * normal[0], normal[1], sin * normal[0]],
+            uv(i as f32 / sides as f32, cos, sin),
+        );
+    }
 
-            temp_vec.push(step * x as f32 - 0.5 + step);
-            temp_vec.push(step * y as f32 - 0.5 + step);
-            temp_vec.push(-0.5);
+    first
+}
 
-            temp_vec.push(step * x as f32 - 0.5);
-            temp_vec.push(step * y as f32 - 0.5);
-            temp_vec.push(-0.5);
+// The caps of the cylinder and tube are circles in the bottom half of the texture, the top cap on the left and the bottom cap on the right.
+// The scale is the radius of the ring divided by the radius of the cap, so the outer edge of the cap touches the edges of its half of the texture.
+// Both caps are seen from the outside, so the top cap is mirrored on the Z axis compared to the bottom cap.
+fn top_cap_uv(scale: f32, cos: f32, sin: f32) -> [f32; 2] {
+    [0.25 + cos * scale * 0.25, 0.25 - sin * scale * 0.25]
+}
 
-            temp_vec.push(step * x as f32 - 0.5 + step);
-            temp_vec.push(step * y as f32 - 0.5 + step);
-            temp_vec.push(-0.5);
+fn bottom_cap_uv(scale: f32, cos: f32, sin: f32) -> [f32; 2] {
+    [0.75 + cos * scale * 0.25, 0.25 + sin * scale * 0.25]
+}
 
-            temp_vec.push(step * x as f32 - 0.5 + step);
-            temp_vec.push(step * y as f32 - 0.5);
-            temp_vec.push(-0.5);
+// Adds a wall around the Y axis from y = -0.5 to 0.5 that is split into height_segments rows, see arc_ring(). V runs from bottom_v to top_v.
+// The wall faces away from the Y axis, or towards it when inside is true. The angle runs clockwise when seen from the outside so U runs the other way to keep the texture readable, seen from the inside U runs along with it.
+#[allow(clippy::too_many_arguments)]
+fn wall(shape_data: &mut ShapeData, sides: usize, start_angle: f32, sweep_angle: f32, radius: f32, height_segments: usize, inside: bool, bottom_v: f32, top_v: f32) {
+    let normal = if inside { [-1.0, 0.0] } else { [1.0, 0.0] };
+    let rings: Vec<u32> = (0..=height_segments)
+        .map(|k| {
+            let t = k as f32 / height_segments as f32;
+            let v = bottom_v + (top_v - bottom_v) * t;
+            arc_ring(shape_data, sides, start_angle, sweep_angle, radius, t - 0.5, normal, |u, _, _| if inside { [u, v] } else { [1.0 - u, v] })
+        })
+        .collect();
+
+    for k in 1..rings.len() {
+        let (lower, upper) = (rings[k - 1], rings[k]);
+        for i in 0..sides as u32 {
+            if inside {
+                shape_data.push_triangle(upper + i + 1, upper + i, lower + i);
+                shape_data.push_triangle(lower + i, lower + i + 1, upper + i + 1);
+            } else {
+                shape_data.push_triangle(upper + i, upper + i + 1, lower + i);
+                shape_data.push_triangle(lower + i + 1, lower + i, upper + i + 1);
+            }
         }
     }
+}
 
-    for y in 0..subdivisions {
-        for x in 0..subdivisions {
-            temp_vec.push(step * x as f32 - 0.5);
-            temp_vec.push(0.5);
-            temp_vec.push(step * y as f32 - 0.5);
+// Adds a flat cap at height y from the inner radius to the outer radius that is split into cap_segments rings, see arc_ring(). Up is 1 for a cap that faces up and -1 for a cap that faces down.
+// An inner radius of 0 closes the cap with a single vertex in the center. The uv function is top_cap_uv() or bottom_cap_uv(), the outer ring touches the edges of its half of the texture.
+#[allow(clippy::too_many_arguments)]
+fn cap(shape_data: &mut ShapeData, sides: usize, start_angle: f32, sweep_angle: f32, inner_radius: f32, outer_radius: f32, cap_segments: usize, y: f32, up: f32, uv: fn(f32, f32, f32) -> [f32; 2]) {
+    let scale = |radius: f32| if outer_radius > 0.0 { radius / outer_radius } else { 0.0 };
+
+    let center = (inner_radius == 0.0).then(|| shape_data.push_vertex([0.0, y, 0.0], [0.0, up, 0.0], uv(0.0, 1.0, 0.0)));
+    let rings: Vec<u32> = (if center.is_some() { 1 } else { 0 }..=cap_segments)
+        .map(|k| {
+            let radius = inner_radius + (outer_radius - inner_radius) * k as f32 / cap_segments as f32;
+            arc_ring(shape_data, sides, start_angle, sweep_angle, radius, y, [0.0, up], |_, cos, sin| uv(scale(radius), cos, sin))
+        })
+        .collect();
+
+    if let Some(center) = center {
+        for i in 0..sides as u32 {
+            if up > 0.0 {
+                shape_data.push_triangle(rings[0] + i + 1, rings[0] + i, center);
+            } else {
+                shape_data.push_triangle(rings[0] + i, rings[0] + i + 1, center);
+            }
+        }
+    }
 
-            temp_vec.push(step * x as f32 - 0.5);
-            temp_vec.push(0.5);
-            temp_vec.push(step * y as f32 - 0.5 + step);
+    for k in 1..rings.len() {
+        let (inner, outer) = (rings[k - 1], rings[k]);
+        for i in 0..sides as u32 {
+            if up > 0.0 {
+                shape_data.push_triangle(inner + i, inner + i + 1, outer + i);
+                shape_data.push_triangle(outer + i, inner + i + 1, outer + i + 1);
+            } else {
+                shape_data.push_triangle(inner + i + 1, inner + i, outer + i);
+                shape_data.push_triangle(inner + i + 1, outer + i, outer + i + 1);
+            }
+        }
+    }
+}
 
-            temp_vec.push(step * x as f32 - 0.5 + step);
-            temp_vec.push(0.5);
-            temp_vec.push(step * y as f32 - 0.5 + step);
+// Adds the flat face where a slice of a cylinder or tube is cut off at the given angle, from the inner radius to the outer radius over the whole height.
+// The face is split into the same rings as the caps and the same rows as the walls so it shares every vertex position along its edges.
+// The face at the start angle looks backwards along the ring and the face at the end looks forwards, so both face away from the slice.
+// There is no free room left on the texture, so the cut faces share the squares of the caps: the start face the square of the top cap and the end face the square of the bottom cap. U runs along the radius up to the outer radius and V up the height.
+#[allow(clippy::too_many_arguments)]
+fn cut_face(shape_data: &mut ShapeData, angle: f32, inner_radius: f32, outer_radius: f32, cap_segments: usize, height_segments: usize, end: bool) {
+    let (sin, cos) = angle.sin_cos();
+    let direction = if end { 1.0 } else { -1.0 };
+    let normal = [-sin * direction, 0.0, cos * direction];
+    let first = shape_data.nr_of_vertices() as u32;
+
+    // Seen from the outside the end face runs from the inner to the outer radius, the start face the other way round.
+    for j in 0..=height_segments {
+        let y = j as f32 / height_segments as f32 - 0.5;
+        for i in 0..=cap_segments {
+            let radius = inner_radius + (outer_radius - inner_radius) * i as f32 / cap_segments as f32;
+            let s = if outer_radius > 0.0 { radius / outer_radius } else { 0.0 };
+            let u = if end { 0.5 + s * 0.5 } else { (1.0 - s) * 0.5 };
+            shape_data.push_vertex([cos * radius, y, sin * radius], normal, [u, (y + 0.5) * 0.5]);
+        }
+    }
 
-            temp_vec.push(step * x as f32 - 0.5);
-            temp_vec.push(0.5);
-            temp_vec.push(step * y as f32 - 0.5);
+    let columns = cap_segments as u32 + 1;
+    for j in 0..height_segments as u32 {
+        for i in 0..cap_segments as u32 {
+            let inner_bottom = first + j * columns + i;
+            let (outer_bottom, inner_top) = (inner_bottom + 1, inner_bottom + columns);
+            let outer_top = inner_top + 1;
+
+            if end {
+                shape_data.push_triangle(inner_bottom, outer_bottom, outer_top);
+                shape_data.push_triangle(inner_bottom, outer_top, inner_top);
+            } else {
+                shape_data.push_triangle(inner_bottom, outer_top, outer_bottom);
+                shape_data.push_triangle(inner_bottom, inner_top, outer_top);
+            }
+        }
+    }
+}
 
-            temp_vec.push(step * x as f32 - 0.5 + step);
-            temp_vec.push(0.5);
-            temp_vec.push(step * y as f32 - 0.5 + step);
+// A point of a profile that is turned around the Y axis by revolve(). The normal is given as [outwards, up] just like for ring() and v is the V coordinate of the ring.
+#[derive(Clone, Copy)]
+struct ProfilePoint {
+    radius: f32,
+    y: f32,
+    normal: [f32; 2],
+    v: f32,
+}
 
+// Turns the profile around the Y axis with a ring per point and connects every ring to the next one. U runs along the wall the same way as on the cylinder.
+// The profile runs upwards along the outside of the surface, so the triangles face away from the Y axis.
+fn revolve(shape_data: &mut ShapeData, sides: usize, profile: &[ProfilePoint]) {
+    arc_revolve(shape_data, sides, 0.0, 2.0 * PI, profile);
+}
 
-            temp_vec.push(step * x as f32 - 0.5 + step);
-            temp_vec.push(0.5);
-            temp_vec.push(step * y as f32 - 0.5);
+// Turns the profile from the start angle over the sweep angle, see revolve() and arc_ring().
+fn arc_revolve(shape_data: &mut ShapeData, sides: usize, start_angle: f32, sweep_angle: f32, profile: &[ProfilePoint]) {
+    let rings: Vec<u32> = profile.iter()
+        .map(|point| arc_ring(shape_data, sides, start_angle, sweep_angle, point.radius, point.y, point.normal, |t, _, _| [1.0 - t, point.v]))
+        .collect();
+
+    for k in 1..profile.len() {
+        let (lower, upper) = (rings[k - 1], rings[k]);
+
+        // Where the profile touches the Y axis the ring is a single point, like the tip of a cone. The triangle with two corners on that point has no area, so it is left out.
+        for i in 0..sides as u32 {
+            if profile[k].radius > 0.0 {
+                shape_data.push_triangle(upper + i, upper + i + 1, lower + i);
+            }
+            if profile[k - 1].radius > 0.0 {
+                shape_data.push_triangle(lower + i + 1, lower + i, upper + i + 1);
+            }
         }
     }
+}
+
+// Returns the ShapeData of a cylinder with a height of 1 around the Y axis. The wall is split into height_segments rows and the caps into cap_segments rings.
+// The cylinder runs from the start angle over the sweep angle, see arc_ring(). A sweep below 2 PI gives a slice, side caps close its cut faces.
+#[allow(clippy::too_many_arguments)]
+fn cylinder(sides: usize, radius: f32, height_segments: usize, cap_segments: usize, start_angle: f32, sweep_angle: f32, side_caps: bool) -> ShapeData {
+    let mut shape_data = ShapeData::new();
+
+    shape_data.begin_group("top");
+    cap(&mut shape_data, sides, start_angle, sweep_angle, 0.0, radius, cap_segments, 0.5, 1.0, top_cap_uv);
+
+    shape_data.begin_group("side");
+    wall(&mut shape_data, sides, start_angle, sweep_angle, radius, height_segments, false, 0.5, 1.0);
+
+    shape_data.begin_group("bottom");
+    cap(&mut shape_data, sides, start_angle, sweep_angle, 0.0, radius, cap_segments, -0.5, -1.0, bottom_cap_uv);
+
+    if side_caps && sweep_angle < 2.0 * PI {
+        shape_data.begin_group("start");
+        cut_face(&mut shape_data, start_angle, 0.0, radius, cap_segments, height_segments, false);
+        shape_data.begin_group("end");
+        cut_face(&mut shape_data, start_angle + sweep_angle, 0.0, radius, cap_segments, height_segments, true);
+    }
+
+    shape_data
+}
+
+// ==== TUBE
+
+// Returns the ShapeData of a tube with a height of 1 around the Y axis.
+// The outer wall uses the top quarter of the texture and the inner wall the quarter below it. The rings use the same circles as the caps of the cylinder.
+// Like the cylinder, the walls are split into height_segments rows and the rings into cap_segments rings, and the tube runs from the start angle over the sweep angle with side caps to close the cut faces of a slice.
+#[allow(clippy::too_many_arguments)]
+fn tube(sides: usize, inner_radius: f32, outer_radius: f32, height_segments: usize, cap_segments: usize, start_angle: f32, sweep_angle: f32, side_caps: bool) -> ShapeData {
+    let mut shape_data = ShapeData::new();
 
-    for y in 0..subdivisions {
-        for x in 0..subdivisions {
-            temp_vec.push(step * x as f32 - 0.5 + step);
-            temp_vec.push(-0.5);
-            temp_vec.push(step * y as f32 - 0.5 + step);
+    shape_data.begin_group("top");
+    cap(&mut shape_data, sides, start_angle, sweep_angle, inner_radius, outer_radius, cap_segments, 0.5, 1.0, top_cap_uv);
 
-            temp_vec.push(step * x as f32 - 0.5);
-            temp_vec.push(-0.5);
-            temp_vec.push(step * y as f32 - 0.5 + step);
+    shape_data.begin_group("outside");
+    wall(&mut shape_data, sides, start_angle, sweep_angle, outer_radius, height_segments, false, 0.75, 1.0);
 
-            temp_vec.push(step * x as f32 - 0.5);
-            temp_vec.push(-0.5);
-            temp_vec.push(step * y as f32 - 0.5);
+    shape_data.begin_group("inside");
+    wall(&mut shape_data, sides, start_angle, sweep_angle, inner_radius, height_segments, true, 0.5, 0.75);
 
-            temp_vec.push(step * x as f32 - 0.5 + step);
-            temp_vec.push(-0.5);
-            temp_vec.push(step * y as f32 - 0.5);
+    shape_data.begin_group("bottom");
+    cap(&mut shape_data, sides, start_angle, sweep_angle, inner_radius, outer_radius, cap_segments, -0.5, -1.0, bottom_cap_uv);
+
+    if side_caps && sweep_angle < 2.0 * PI {
+        shape_data.begin_group("start");
+        cut_face(&mut shape_data, start_angle, inner_radius, outer_radius, cap_segments, height_segments, false);
+        shape_data.begin_group("end");
+        cut_face(&mut shape_data, start_angle + sweep_angle, inner_radius, outer_radius, cap_segments, height_segments, true);
+    }
 
-            temp_vec.push(step * x as f32 - 0.5 + step);
-            temp_vec.push(-0.5);
-            temp_vec.push(step * y as f32 - 0.5 + step);
+    shape_data
+}
 
-            temp_vec.push(step * x as f32 - 0.5);
-            temp_vec.push(-0.5);
-            temp_vec.push(step * y as f32 - 0.5);
+// ==== CONE
+
+// Returns the ShapeData of a cone with a height of 1 around the Y axis. A top radius of 0 gives a pointed cone, any other top radius a frustum.
+// The wall is split into height_segments rings. The UV layout is the same as the cylinder, the caps are scaled so the largest one touches the edges of its half of the texture.
+fn cone(sides: usize, bottom_radius: f32, top_radius: f32, height_segments: usize, capped: bool) -> ShapeData {
+    let mut shape_data = ShapeData::new();
+    let largest = bottom_radius.max(top_radius);
+
+    // Top, a pointed cone has nothing to cap.
+    if capped && top_radius > 0.0 {
+        shape_data.begin_group("top");
+        let scale = top_radius / largest;
+        let center = shape_data.push_vertex([0.0, 0.5, 0.0], [0.0, 1.0, 0.0], top_cap_uv(0.0, 1.0, 0.0));
+        let top = ring(&mut shape_data, sides, top_radius, 0.5, [0.0, 1.0], |_, cos, sin| top_cap_uv(scale, cos, sin));
+        for i in 0..sides as u32 {
+            shape_data.push_triangle(top + i + 1, top + i, center);
         }
     }
 
-    for y in 0..subdivisions {
-        for x in 0..subdivisions {
-            temp_vec.push(0.5);
-            temp_vec.push(step * x as f32 - 0.5 + step);
-            temp_vec.push(step * y as f32 - 0.5 + step);
+    // Side. When the top is smaller than the bottom the wall leans inwards, so the normal tilts upwards by the difference between the radii.
+    shape_data.begin_group("side");
+    let slope = bottom_radius - top_radius;
+    let length = (1.0 + slope * slope).sqrt();
+    let normal = [1.0 / length, slope / length];
+    let profile: Vec<ProfilePoint> = (0..=height_segments)
+        .map(|k| {
+            let t = k as f32 / height_segments as f32;
+            ProfilePoint { radius: bottom_radius + (top_radius - bottom_radius) * t, y: t - 0.5, normal, v: 0.5 + t * 0.5 }
+        })
+        .collect();
+    revolve(&mut shape_data, sides, &profile);
 
-            temp_vec.push(0.5);
-            temp_vec.push(step * x as f32 - 0.5);
-            temp_vec.push(step * y as f32 - 0.5 + step);
+    // Bottom
+    if capped && bottom_radius > 0.0 {
+        shape_data.begin_group("bottom");
+        let scale = bottom_radius / largest;
+        let center = shape_data.push_vertex([0.0, -0.5, 0.0], [0.0, -1.0, 0.0], bottom_cap_uv(0.0, 1.0, 0.0));
+        let bottom = ring(&mut shape_data, sides, bottom_radius, -0.5, [0.0, -1.0], |_, cos, sin| bottom_cap_uv(scale, cos, sin));
+        for i in 0..sides as u32 {
+            shape_data.push_triangle(bottom + i, bottom + i + 1, center);
+        }
+    }
 
-            temp_vec.push(0.5);
-            temp_vec.push(step * x as f32 - 0.5);
-            temp_vec.push(step * y as f32 - 0.5);
+    shape_data
+}
 
-            temp_vec.push(0.5);
-            temp_vec.push(step * x as f32 - 0.5 + step);
-            temp_vec.push(step * y as f32 - 0.5);
+// ==== PRISM AND PYRAMID
+
+// Returns the ShapeData of a solid with a regular polygon as its base around the Y axis, which is a prism when both radii are the same and a pyramid when the top radius is smaller.
+// The corners of the polygon lie on the circle with the radius, stepped around the Y axis just like the cylinder, see arc_ring(). The bottom lies at -height / 2 and the top at height / 2.
+// The sides are split into height_segments rows and every row is turned by its share of the twist, so the top ends up turned by the whole twist.
+// The normals are made by hard_edges() so every side stays flat. The UV layout is the same as the cylinder.
+fn polygon_solid(sides: usize, bottom_radius: f32, top_radius: f32, height: f32, twist: f32, height_segments: usize) -> ShapeData {
+    let mut shape_data = ShapeData::new();
+    let largest = bottom_radius.max(top_radius);
+    let scale = |radius: f32| if largest > 0.0 { radius / largest } else { 0.0 };
+
+    // Top, a pointed pyramid has nothing to cap.
+    if top_radius > 0.0 {
+        shape_data.begin_group("top");
+        let center = shape_data.push_vertex([0.0, height / 2.0, 0.0], [0.0, 1.0, 0.0], top_cap_uv(0.0, 1.0, 0.0));
+        let top = arc_ring(&mut shape_data, sides, twist, 2.0 * PI, top_radius, height / 2.0, [0.0, 1.0], |_, cos, sin| top_cap_uv(scale(top_radius), cos, sin));
+        for i in 0..sides as u32 {
+            shape_data.push_triangle(top + i + 1, top + i, center);
+        }
+    }
 
-            temp_vec.push(0.5);
-            temp_vec.push(step * x as f32 - 0.5 + step);
-            temp_vec.push(step * y as f32 - 0.5 + step);
+    shape_data.begin_group("side");
+    let rows: Vec<(f32, u32)> = (0..=height_segments)
+        .map(|k| {
+            let t = k as f32 / height_segments as f32;
+            let radius = bottom_radius + (top_radius - bottom_radius) * t;
+            (radius, arc_ring(&mut shape_data, sides, twist * t, 2.0 * PI, radius, height * (t - 0.5), [1.0, 0.0], |u, _, _| [1.0 - u, 0.5 + t * 0.5]))
+        })
+        .collect();
+    for k in 1..rows.len() {
+        let ((lower_radius, lower), (upper_radius, upper)) = (rows[k - 1], rows[k]);
+
+        // The tip of a pyramid is a single point, see revolve().
+        for i in 0..sides as u32 {
+            if upper_radius > 0.0 {
+                shape_data.push_triangle(upper + i, upper + i + 1, lower + i);
+            }
+            if lower_radius > 0.0 {
+                shape_data.push_triangle(lower + i + 1, lower + i, upper + i + 1);
+            }
+        }
+    }
 
-            temp_vec.push(0.5);
-            temp_vec.push(step * x as f32 - 0.5);
-            temp_vec.push(step * y as f32 - 0.5);
+    if bottom_radius > 0.0 {
+        shape_data.begin_group("bottom");
+        let center = shape_data.push_vertex([0.0, -height / 2.0, 0.0], [0.0, -1.0, 0.0], bottom_cap_uv(0.0, 1.0, 0.0));
+        let bottom = ring(&mut shape_data, sides, bottom_radius, -height / 2.0, [0.0, -1.0], |_, cos, sin| bottom_cap_uv(scale(bottom_radius), cos, sin));
+        for i in 0..sides as u32 {
+            shape_data.push_triangle(bottom + i, bottom + i + 1, center);
         }
     }
 
-    for y in 0..subdivisions {
-        for x in 0..subdivisions {
-            temp_vec.push(-0.5);
-            temp_vec.push(step * x as f32 - 0.5);
-            temp_vec.push(step * y as f32 - 0.5 + step);
+    shape_data
+}
+
+// ==== LATHE
+
+// Returns the ShapeData of the surface that the profile makes when it is turned around the Y axis from the start angle over the sweep angle.
+// The profile holds a radius and a height for every point. It should run from the bottom to the top along the outside of the surface so the triangles face outwards, a point with a radius of 0 closes the surface on the Y axis.
+// The normal of a point lies halfway between the normals of the two parts of the profile next to it, so corners of the profile get smoothed like the rest. Turn smooth off for sharp corners.
+fn lathe(profile: &[f32], radial_segments: usize, start_angle: f32, sweep_angle: f32) -> ShapeData {
+    let mut shape_data = ShapeData::new();
+    shape_data.begin_group("lathe");
+
+    let points: Vec<[f32; 2]> = profile.chunks(2).map(|point| [point[0], point[1]]).collect();
+
+    // The normal of a part of the profile points to its right, which is away from the Y axis while the profile runs upwards.
+    let part_normal = |a: [f32; 2], b: [f32; 2]| {
+        let [outwards, up, _] = normalize([b[1] - a[1], a[0] - b[0], 0.0]);
+        [outwards, up]
+    };
+    let lengths: Vec<f32> = points.windows(2).map(|part| ((part[1][0] - part[0][0]).powi(2) + (part[1][1] - part[0][1]).powi(2)).sqrt()).collect();
+    let total: f32 = lengths.iter().sum();
+
+    let mut travelled = 0.0;
+    let profile: Vec<ProfilePoint> = (0..points.len())
+        .map(|k| {
+            let before = (k > 0).then(|| part_normal(points[k - 1], points[k]));
+            let after = (k + 1 < points.len()).then(|| part_normal(points[k], points[k + 1]));
+            let [outwards, up] = [before, after].iter().flatten().fold([0.0, 0.0], |sum, normal| [sum[0] + normal[0], sum[1] + normal[1]]);
+            let [outwards, up, _] = normalize([outwards, up, 0.0]);
+
+            if k > 0 {
+                travelled += lengths[k - 1];
+            }
+            let v = if total > 0.0 { travelled / total } else { 0.0 };
+            ProfilePoint { radius: points[k][0], y: points[k][1], normal: [outwards, up], v }
+        })
+        .collect();
+    arc_revolve(&mut shape_data, radial_segments, start_angle, sweep_angle, &profile);
+
+    shape_data
+}
 
-            temp_vec.push(-0.5);
-            temp_vec.push(step * x as f32 - 0.5 + step);
-            temp_vec.push(step * y as f32 - 0.5 + step);
+// ==== EXTRUDE
+
+// Returns the ShapeData of the outline with its holes pushed out along the Z axis, with the back at -depth / 2 and the front at depth / 2.
+// The outline and every hole hold an x and a y for every corner, in either order around the polygon. The caps are split into triangles by ear clipping, see triangulate.rs.
+// A bevel rounds the edges between the sides and the caps with a quarter circle of bevel_segments steps, which pulls the caps inwards by the bevel size. It can't be larger than half the depth, and validation refuses a bevel that doesn't fit in the polygon, see bevel_fits().
+// Every side gets the normals of its own edge so the corners of the polygon stay sharp while the bevels are smooth.
+fn extrude(outline: &[f32], holes: &[Vec<f32>], depth: f32, bevel_size: f32, bevel_segments: usize) -> ShapeData {
+    let mut shape_data = ShapeData::new();
+
+    let outline = extrude_contour(outline, true);
+    let holes: Vec<Vec<[f32; 2]>> = holes.iter().map(|hole| extrude_contour(hole, false)).collect();
+    let contours: Vec<&Vec<[f32; 2]>> = std::iter::once(&outline).chain(&holes).collect();
+
+    let distance = |a: [f32; 2], b: [f32; 2]| ((b[0] - a[0]).powi(2) + (b[1] - a[1]).powi(2)).sqrt();
+
+    // The profile runs from the back to the front as the distance the polygon is pulled inwards, the z and the normal as a part outwards and a part along the Z axis.
+    let bevel = bevel_size.min(depth / 2.0);
+    let steps = if bevel > 0.0 { bevel_segments } else { 0 };
+    let mut profile: Vec<(f32, f32, [f32; 2])> = Vec::new();
+    for side in [-1.0, 1.0] {
+        for j in 0..=steps {
+            let step = if side < 0.0 { steps - j } else { j };
+            let angle = if steps > 0 { FRAC_PI_2 * step as f32 / steps as f32 } else { 0.0 };
+            let (sin, cos) = angle.sin_cos();
+            profile.push((bevel * (cos - 1.0), side * (depth / 2.0 - bevel + bevel * sin), [cos, side * sin]));
+        }
+    }
+    // Without a wall between the bevels both quarter circles meet in the same point.
+    if bevel >= depth / 2.0 {
+        profile.remove(steps + 1);
+    }
+    let mut travelled = 0.0;
+    let mut v: Vec<f32> = vec![0.0];
+    for part in profile.windows(2) {
+        travelled += distance([part[0].0, part[0].1], [part[1].0, part[1].1]);
+        v.push(travelled);
+    }
+    let v: Vec<f32> = v.iter().map(|length| 0.5 + 0.5 * if travelled > 0.0 { length / travelled } else { 0.0 }).collect();
+
+    // Front, the caps lie inside the bevel so they are pulled inwards by the whole bevel.
+    let caps: Vec<Vec<[f32; 2]>> = contours.iter().map(|corners| inset_contour(corners, -bevel)).collect();
+    let corners: Vec<[f32; 2]> = caps.iter().flatten().copied().collect();
+    let polygon = triangulate::bridge_holes(&caps[0], &caps[1..]);
+    let merged: Vec<[f32; 2]> = polygon.iter().map(|&i| corners[i]).collect();
+    let triangles = triangulate::triangulate(&merged);
+
+    let bounds = outline.iter().fold([f32::INFINITY, f32::INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY], |bounds, corner| {
+        [bounds[0].min(corner[0]), bounds[1].min(corner[1]), bounds[2].max(corner[0]), bounds[3].max(corner[1])]
+    });
+    let size = (bounds[2] - bounds[0]).max(bounds[3] - bounds[1]).max(f32::MIN_POSITIVE);
+    let cap = |shape_data: &mut ShapeData, side: f32| {
+        let first = shape_data.vertices.len() as u32 / 3;
+        for corner in &corners {
+            let (u, v) = ((corner[0] - bounds[0]) / size, (corner[1] - bounds[1]) / size);
+            // The back is seen from behind, so its texture is mirrored to keep it readable.
+            let uv = if side > 0.0 { [u * 0.5, v * 0.5] } else { [1.0 - u * 0.5, v * 0.5] };
+            shape_data.push_vertex([corner[0], corner[1], side * depth / 2.0], [0.0, 0.0, side], uv);
+        }
+        for triangle in &triangles {
+            let [a, b, c] = triangle.map(|k| first + polygon[k] as u32);
+            if side > 0.0 {
+                shape_data.push_triangle(a, b, c);
+            } else {
+                shape_data.push_triangle(a, c, b);
+            }
+        }
+    };
+    shape_data.begin_group("front");
+    cap(&mut shape_data, 1.0);
+
+    // Sides, every edge gets its own strip of quads along the profile. U runs along every outline and hole on its own.
+    shape_data.begin_group("side");
+    for corners in &contours {
+        let rows: Vec<Vec<[f32; 2]>> = profile.iter().map(|&(offset, _, _)| inset_contour(corners, offset)).collect();
+        let lengths: Vec<f32> = (0..corners.len()).map(|k| distance(corners[k], corners[(k + 1) % corners.len()])).collect();
+        let perimeter: f32 = lengths.iter().sum();
+
+        let mut u = 0.0;
+        for k in 0..corners.len() {
+            let next = (k + 1) % corners.len();
+            let normal = edge_normal(corners[k], corners[next]);
+            let (start_u, end_u) = (u / perimeter, (u + lengths[k]) / perimeter);
+            u += lengths[k];
+
+            let first = shape_data.vertices.len() as u32 / 3;
+            for (j, &(_, z, [outwards, forwards])) in profile.iter().enumerate() {
+                let normal = [normal[0] * outwards, normal[1] * outwards, forwards];
+                shape_data.push_vertex([rows[j][k][0], rows[j][k][1], z], normal, [start_u, v[j]]);
+                shape_data.push_vertex([rows[j][next][0], rows[j][next][1], z], normal, [end_u, v[j]]);
+            }
+            for j in 0..profile.len() as u32 - 1 {
+                let (a0, b0, a1, b1) = (first + 2 * j, first + 2 * j + 1, first + 2 * j + 2, first + 2 * j + 3);
+                shape_data.push_triangle(a0, b0, b1);
+                shape_data.push_triangle(a0, b1, a1);
+            }
+        }
+    }
 
-            temp_vec.push(-0.5);
-            temp_vec.push(step * x as f32 - 0.5);
-            temp_vec.push(step * y as f32 - 0.5);
+    shape_data.begin_group("back");
+    cap(&mut shape_data, -1.0);
 
-            temp_vec.push(-0.5);
-            temp_vec.push(step * x as f32 - 0.5 + step);
-            temp_vec.push(step * y as f32 - 0.5 + step);
+    shape_data
+}
 
-            temp_vec.push(-0.5);
-            temp_vec.push(step * x as f32 - 0.5 + step);
-            temp_vec.push(step * y as f32 - 0.5);
 
-            temp_vec.push(-0.5);
-            temp_vec.push(step * x as f32 - 0.5);
-            temp_vec.push(step * y as f32 - 0.5);
-        }
+// Returns the corners of the polygon, counter clockwise for the outline and clockwise for a hole so the polygon always lies on the left of an edge. Corners that repeat would give an edge without a direction.
+fn extrude_contour(polygon: &[f32], counter_clockwise: bool) -> Vec<[f32; 2]> {
+    let mut corners: Vec<[f32; 2]> = polygon.chunks(2).map(|corner| [corner[0], corner[1]]).collect();
+    corners.dedup();
+    if corners.len() > 1 && corners.first() == corners.last() {
+        corners.pop();
     }
+    if (triangulate::signed_area(&corners) > 0.0) != counter_clockwise {
+        corners.reverse();
+    }
+    corners
+}
 
-    return temp_vec;
+// The normal of an edge points to its right, away from the polygon.
+fn edge_normal(a: [f32; 2], b: [f32; 2]) -> [f32; 2] {
+    let [x, y, _] = normalize([b[1] - a[1], a[0] - b[0], 0.0]);
+    [x, y]
 }
 
-// ==== SPHERE
+// Moves every edge of the contour outwards by the offset, or inwards when it is negative. A corner moves along the bisector of its edges, far enough that both edges move by the offset.
+fn inset_contour(corners: &[[f32; 2]], offset: f32) -> Vec<[f32; 2]> {
+    (0..corners.len())
+        .map(|k| {
+            let (previous, corner, next) = (corners[(k + corners.len() - 1) % corners.len()], corners[k], corners[(k + 1) % corners.len()]);
+            let (n1, n2) = (edge_normal(previous, corner), edge_normal(corner, next));
+            let factor = 1.0 + n1[0] * n2[0] + n1[1] * n2[1];
+            // An edge that turns all the way back has no bisector, so the corner only follows the edge before it.
+            let miter = if factor > 1e-3 { [(n1[0] + n2[0]) / factor, (n1[1] + n2[1]) / factor] } else { n1 };
+            [corner[0] + offset * miter[0], corner[1] + offset * miter[1]]
+        })
+        .collect()
+}
+
+// Returns true when the caps of the extrusion still fit after they are pulled inwards by the bevel. A bevel that is larger than a wall of the polygon turns an edge around, makes the edges cross
+// or moves a hole out of the outline.
+fn bevel_fits(outline: &[f32], holes: &[Vec<f32>], depth: f32, bevel_size: f32) -> bool {
+    let bevel = bevel_size.min(depth / 2.0);
+    if bevel <= 0.0 {
+        return true;
+    }
 
-// Returns the amount of triangles the wanted sphere consists off.
-fn nr_of_sphere_triangles(subdivisions: usize) -> usize {
-    return nr_of_cube_triangles(subdivisions);
+    let contours: Vec<Vec<[f32; 2]>> = std::iter::once(extrude_contour(outline, true)).chain(holes.iter().map(|hole| extrude_contour(hole, false))).collect();
+    let caps: Vec<Vec<[f32; 2]>> = contours.iter().map(|corners| inset_contour(corners, -bevel)).collect();
+
+    let keeps_direction = contours.iter().zip(&caps).all(|(corners, cap)| {
+        (0..corners.len()).all(|k| {
+            let next = (k + 1) % corners.len();
+            let (edge, inset) = ([corners[next][0] - corners[k][0], corners[next][1] - corners[k][1]], [cap[next][0] - cap[k][0], cap[next][1] - cap[k][1]]);
+            edge[0] * inset[0] + edge[1] * inset[1] > 0.0
+        })
+    });
+
+    let holes_inside = (1..caps.len()).all(|h| {
+        let corner = caps[h][0];
+        triangulate::contains(&caps[0], corner) && (1..caps.len()).all(|other| other == h || !triangulate::contains(&caps[other], corner))
+    });
+
+    keeps_direction && holes_inside && !triangulate::contours_cross(&caps)
 }
 
-// Returns a Vec<f32> with all the vertices. Every trio of f32's forms a vector3.
-fn sphere(subdivisions: usize) -> Vec<f32> {
-    let mut temp_vec = cube(subdivisions);
-    let mut length: f32;
+// ==== SWEEP
+
+// Returns the ShapeData of the section moved along the path, like a pipe or a rail. The path holds an x, a y and a z for every point, the section an x and a y for every corner.
+// Between two points the path follows a Catmull-Rom spline in path_segments steps, so one step gives the straight lines between the points. A path that ends where it starts is closed and gets no caps.
+// The section is carried along the path by rotation minimizing frames, which turn it as little as possible so it doesn't twist by itself in curves. The x axis of the section starts out level and its y axis points up as far as the path allows.
+// Every point can scale and turn the section by its value in scales and twists, which are blended between the points. The twist turns the section at the end against the start, spread along the length of the path.
+fn sweep(path: &[f32], section: &[[f32; 2]], scales: &[f32], twists: &[f32], path_segments: usize, twist: f32, capped: bool) -> ShapeData {
+    let mut shape_data = ShapeData::new();
+
+    let points: Vec<[f32; 3]> = path.chunks(3).map(|point| [point[0], point[1], point[2]]).collect();
+    let count = points.len();
+    let closed = count > 2 && points[0] == points[count - 1];
+    // The spline needs the points around every step. A closed path wraps around, an open path repeats its ends.
+    let point = |k: isize| {
+        if closed {
+            points[k.rem_euclid(count as isize - 1) as usize]
+        } else {
+            points[k.clamp(0, count as isize - 1) as usize]
+        }
+    };
+    let scale_at = |k: usize| scales.get(k).copied().unwrap_or(1.0);
+    let twist_at = |k: usize| twists.get(k).copied().unwrap_or(0.0);
+
+    // The samples along the path as their position, scale and twist.
+    let mut samples: Vec<([f32; 3], f32, f32)> = Vec::new();
+    for k in 0..count - 1 {
+        let [p0, p1, p2, p3] = [point(k as isize - 1), point(k as isize), point(k as isize + 1), point(k as isize + 2)];
+        for step in 0..path_segments {
+            let t = step as f32 / path_segments as f32;
+            let position: [f32; 3] = std::array::from_fn(|axis| {
+                let (p0, p1, p2, p3) = (p0[axis], p1[axis], p2[axis], p3[axis]);
+                0.5 * (2.0 * p1 + (p2 - p0) * t + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t * t + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t * t * t)
+            });
+            let blend = |a: f32, b: f32| a + (b - a) * t;
+            samples.push((position, blend(scale_at(k), scale_at(k + 1)), blend(twist_at(k), twist_at(k + 1))));
+        }
+    }
+    samples.push((points[count - 1], scale_at(count - 1), twist_at(count - 1)));
+    // Points that repeat, or nearly repeat, would give a part of the path without a direction.
+    samples.dedup_by(|sample, previous| dot(sub(sample.0, previous.0), sub(sample.0, previous.0)) <= 1e-12);
+    let n = samples.len();
+    if n < 2 {
+        return shape_data;
+    }
 
-    // To generate a sphere we simply normalize all the vertices of a cube. Length = Sqrt(x^2 + y^2 + z^2) -> x / Length, y / Length, z / Length
-    for i in (0..temp_vec.len()).step_by(3) {
-        length = 
-        (
-            (temp_vec[i    ] * temp_vec[i    ]) + 
-            (temp_vec[i + 1] * temp_vec[i + 1]) + 
-            (temp_vec[i + 2] * temp_vec[i + 2])
-        ).sqrt();
-            
-        temp_vec[i    ] = temp_vec[i    ] / length;
-        temp_vec[i + 1] = temp_vec[i + 1] / length;
-        temp_vec[i + 2] = temp_vec[i + 2] / length;
+    let mut travelled = vec![0.0];
+    for k in 1..n {
+        travelled.push(travelled[k - 1] + dot(sub(samples[k].0, samples[k - 1].0), sub(samples[k].0, samples[k - 1].0)).sqrt());
+    }
+    let total = travelled[n - 1];
+
+    // The direction of the path into and out of every sample. The ends of an open path only have one of them.
+    let incoming: Vec<[f32; 3]> = (0..n)
+        .map(|k| match k {
+            0 if closed => normalize(sub(samples[0].0, samples[n - 2].0)),
+            0 => normalize(sub(samples[1].0, samples[0].0)),
+            _ => normalize(sub(samples[k].0, samples[k - 1].0)),
+        })
+        .collect();
+    let outgoing: Vec<[f32; 3]> = (0..n)
+        .map(|k| match k {
+            _ if k == n - 1 && closed => incoming[1],
+            _ if k == n - 1 => incoming[n - 1],
+            _ => incoming[k + 1],
+        })
+        .collect();
+    let tangents: Vec<[f32; 3]> = (0..n).map(|k| normalize(add(incoming[k], outgoing[k]))).collect();
+
+    // The first frame, the x axis of the section lies flat and the y axis leans towards the Y axis, or the Z axis when the path runs along the Y axis.
+    let up = if tangents[0][1].abs() < 0.99 { [0.0, 1.0, 0.0] } else { [0.0, 0.0, 1.0] };
+    let y_axis = normalize(sub(up, scale(tangents[0], dot(tangents[0], up))));
+    let mut x_axes = vec![cross(y_axis, tangents[0])];
+
+    // The double reflection method: the frame is mirrored onto the next sample and then mirrored again so it lines up with the next tangent.
+    for k in 1..n {
+        let (x_axis, tangent) = (x_axes[k - 1], tangents[k - 1]);
+        let reflect = |vector: [f32; 3], mirror: [f32; 3]| {
+            let length = dot(mirror, mirror);
+            if length > 1e-12 { sub(vector, scale(mirror, 2.0 * dot(mirror, vector) / length)) } else { vector }
+        };
+        let step = sub(samples[k].0, samples[k - 1].0);
+        let (x_axis, tangent) = (reflect(x_axis, step), reflect(tangent, step));
+        x_axes.push(normalize(reflect(x_axis, sub(tangents[k], tangent))));
+    }
+
+    // A closed path can end with its frame turned against the start, that turn is taken back along the length of the path so both ends meet.
+    let mismatch = if closed {
+        let (last, first) = (x_axes[n - 1], x_axes[0]);
+        -dot(cross(first, last), tangents[0]).atan2(dot(first, last))
+    } else {
+        0.0
+    };
+
+    // Every edge of the section gets the normal on its right, which points outwards on a counter clockwise section. A corner lies halfway between its edges.
+    let mut section = section.to_vec();
+    if triangulate::signed_area(&section) < 0.0 {
+        section.reverse();
+    }
+    let m = section.len();
+    let edge_normal = |a: [f32; 2], b: [f32; 2]| normalize([b[1] - a[1], a[0] - b[0], 0.0]);
+    let section_normals: Vec<[f32; 3]> = (0..m).map(|k| normalize(add(edge_normal(section[(k + m - 1) % m], section[k]), edge_normal(section[k], section[(k + 1) % m])))).collect();
+    let mut around = vec![0.0];
+    for k in 0..m {
+        let (a, b) = (section[k], section[(k + 1) % m]);
+        around.push(around[k] + ((b[0] - a[0]).powi(2) + (b[1] - a[1]).powi(2)).sqrt());
+    }
+
+    // The axes of the section at every sample after the twists. A bend in a straight path stretches the section towards the bend so the sides keep their width.
+    let frames: Vec<_> = (0..n)
+        .map(|k| {
+            let along = if total > 0.0 { travelled[k] / total } else { 0.0 };
+            let (sin, cos) = (samples[k].2 + (twist + mismatch) * along).sin_cos();
+            let (x_axis, y_axis) = (x_axes[k], cross(tangents[k], x_axes[k]));
+            let x_axis = add(scale(x_axis, cos), scale(y_axis, sin));
+            (x_axis, cross(tangents[k], x_axis), normalize(sub(outgoing[k], incoming[k])), dot(incoming[k], tangents[k]).max(0.1))
+        })
+        .collect();
+
+    // Side, every sample gets a ring of the corners of the section with the first corner repeated for the seam.
+    let first = shape_data.vertices.len() as u32 / 3;
+    for k in 0..n {
+        let (x_axis, y_axis, bend, miter) = frames[k];
+        let (previous, next) = (k.saturating_sub(1), (k + 1).min(n - 1));
+        // A section that grows along the path tilts the normals backwards, like the wall of a cone.
+        let slope = if travelled[next] > travelled[previous] { (samples[next].1 - samples[previous].1) / (travelled[next] - travelled[previous]) } else { 0.0 };
+
+        for corner in 0..=m {
+            let ([x, y], [nx, ny, _]) = (section[corner % m], section_normals[corner % m]);
+            let offset = add(scale(x_axis, x * samples[k].1), scale(y_axis, y * samples[k].1));
+            let offset = add(offset, scale(bend, dot(offset, bend) * (1.0 / miter - 1.0)));
+            let normal = add(scale(x_axis, nx), scale(y_axis, ny));
+            let normal = add(normal, scale(bend, dot(normal, bend) * (miter - 1.0)));
+            let normal = normalize(sub(normal, scale(tangents[k], slope * (x * nx + y * ny))));
+            let v = if total > 0.0 { travelled[k] / total } else { 0.0 };
+            // A section with a radius of 0 has no length around it.
+            let u = if around[m] > 0.0 { around[corner] / around[m] } else { 0.0 };
+            shape_data.push_vertex(add(samples[k].0, offset), normal, [u, 0.5 + v * 0.5]);
+        }
+    }
+
+    let cap = |shape_data: &mut ShapeData, k: usize, end: bool| {
+        let (x_axis, y_axis, bend, miter) = frames[k];
+        let bounds = section.iter().fold([f32::INFINITY, f32::INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY], |bounds, corner| {
+            [bounds[0].min(corner[0]), bounds[1].min(corner[1]), bounds[2].max(corner[0]), bounds[3].max(corner[1])]
+        });
+        let size = (bounds[2] - bounds[0]).max(bounds[3] - bounds[1]).max(f32::MIN_POSITIVE);
+        let normal = if end { tangents[k] } else { scale(tangents[k], -1.0) };
+
+        let first = shape_data.vertices.len() as u32 / 3;
+        for &[x, y] in &section {
+            let offset = add(scale(x_axis, x * samples[k].1), scale(y_axis, y * samples[k].1));
+            let offset = add(offset, scale(bend, dot(offset, bend) * (1.0 / miter - 1.0)));
+            let (u, v) = ((x - bounds[0]) / size, (y - bounds[1]) / size);
+            // The start is seen from behind, so its texture is mirrored to keep it readable.
+            let uv = if end { [u * 0.5, v * 0.5] } else { [1.0 - u * 0.5, v * 0.5] };
+            shape_data.push_vertex(add(samples[k].0, offset), normal, uv);
+        }
+        for [a, b, c] in triangulate::triangulate(&section) {
+            let [a, b, c] = [a, b, c].map(|corner| first + corner as u32);
+            if end {
+                shape_data.push_triangle(a, b, c);
+            } else {
+                shape_data.push_triangle(a, c, b);
+            }
+        }
+    };
+
+    // A section that is scaled down to a point needs no cap.
+    if capped && !closed && samples[0].1 > 0.0 {
+        shape_data.begin_group("start");
+        cap(&mut shape_data, 0, false);
+    }
+
+    shape_data.begin_group("side");
+    let ring = m as u32 + 1;
+    for k in 0..n as u32 - 1 {
+        for corner in 0..m as u32 {
+            let (a0, b0) = (first + k * ring + corner, first + k * ring + corner + 1);
+            let (a1, b1) = (a0 + ring, b0 + ring);
+            if samples[k as usize].1 > 0.0 {
+                shape_data.push_triangle(a0, b0, b1);
+            }
+            if samples[k as usize + 1].1 > 0.0 {
+                shape_data.push_triangle(a0, b1, a1);
+            }
+        }
+    }
+
+    if capped && !closed && samples[n - 1].1 > 0.0 {
+        shape_data.begin_group("end");
+        cap(&mut shape_data, n - 1, true);
     }
 
-    return temp_vec;
+    shape_data
 }
 
-// ==== CYLINDER
+// ==== TORUS
+
+// Returns the ShapeData of a torus around the Y axis. The center of the tube follows a circle with the major radius, the tube itself has the minor radius.
+// The radial segments split the circle around the Y axis and the tubular segments the circle around the tube. An arc below 2 PI gives a partial torus with open ends.
+fn torus(major_radius: f32, minor_radius: f32, radial_segments: usize, tubular_segments: usize, arc: f32) -> ShapeData {
+    let mut shape_data = ShapeData::new();
+    shape_data.begin_group("torus");
+
+    // Both circles get a duplicate vertex at the end for the seams of the texture.
+    for i in 0..=radial_segments {
+        let s = i as f32 / radial_segments as f32;
+        let (sin, cos) = (s * arc).sin_cos();
+
+        for j in 0..=tubular_segments {
+            let t = j as f32 / tubular_segments as f32;
+            let (tube_sin, tube_cos) = (t * 2.0 * PI).sin_cos();
+
+            // The normal points away from the center of the tube, which lies on the circle around the Y axis.
+            let normal = [cos * tube_cos, tube_sin, sin * tube_cos];
+            let position = [
+                cos * major_radius + normal[0] * minor_radius,
+                normal[1] * minor_radius,
+                sin * major_radius + normal[2] * minor_radius,
+            ];
+            shape_data.push_vertex(position, normal, [1.0 - s, t]);
+        }
+    }
 
-// Returns the amount of triangles the wanted cylinder consists off.
-fn nr_of_cylinder_triangles(sides: usize) -> usize {
-    return (nr_of_disk_triangles(sides) * 2) + (sides * 2);
-}
-
-// Returns a Vec<f32> with all the vertices. Every trio of f32's forms a vector3.
-fn cylinder(sides: usize, radius: f32) -> Vec<f32> {
-    let mut temp_vec: Vec<f32> = Vec::new();
-    let step = 2.0 * PI / sides as f32;
-
-    // Top
-    for i in 0..sides {
-        temp_vec.push(((i + 1) as f32 * step).cos() * radius);
-        temp_vec.push(0.5                                   );
-        temp_vec.push(((i + 1) as f32 * step).sin() * radius);
-            
-        temp_vec.push((i       as f32 * step).cos() * radius);
-        temp_vec.push(0.5                                   );
-        temp_vec.push((i       as f32 * step).sin() * radius);
-            
-        temp_vec.push(0.0                                   );
-        temp_vec.push(0.5                                   );
-        temp_vec.push(0.0                                   );
-    }
-
-    // Outside ring
-    for i in 0..sides {
-        temp_vec.push((i       as f32 * step).cos() * radius);
-        temp_vec.push(0.5                                   );
-        temp_vec.push((i       as f32 * step).sin() * radius);
-            
-        temp_vec.push(((i + 1) as f32 * step).cos() * radius);
-        temp_vec.push(0.5                                   );
-        temp_vec.push(((i + 1) as f32 * step).sin() * radius);
-
-        temp_vec.push((i       as f32 * step).cos() * radius);
-        temp_vec.push(-0.5                                  );
-        temp_vec.push((i       as f32 * step).sin() * radius);
-
-        temp_vec.push(((i + 1) as f32 * step).cos() * radius);
-        temp_vec.push(-0.5                                  );
-        temp_vec.push(((i + 1) as f32 * step).sin() * radius);
-
-        temp_vec.push((i       as f32 * step).cos() * radius);
-        temp_vec.push(-0.5                                  );
-        temp_vec.push((i       as f32 * step).sin() * radius);
-
-        temp_vec.push(((i + 1) as f32 * step).cos() * radius);
-        temp_vec.push(0.5                                   );
-        temp_vec.push(((i + 1) as f32 * step).sin() * radius);
+    let columns = tubular_segments as u32 + 1;
+    for i in 0..radial_segments as u32 {
+        for j in 0..tubular_segments as u32 {
+            let a = i * columns + j;
+            let b = (i + 1) * columns + j;
+            shape_data.push_triangle(a, a + 1, b);
+            shape_data.push_triangle(b, a + 1, b + 1);
+        }
     }
 
-    // Bottom
-    for i in 0..sides {
-        temp_vec.push((i       as f32 * step).cos() * radius);
-        temp_vec.push(-0.5                                  );
-        temp_vec.push((i       as f32 * step).sin() * radius);
+    shape_data
+}
+
+// ==== CAPSULE
+
+// Returns the ShapeData of a capsule around the Y axis: a cylinder of the given length with a hemisphere on both ends, which are split into cap_rings rings.
+// The capsule is a UV sphere that is cut at the equator with both halves moved apart, so V runs from the bottom pole to the top pole over the whole height of the capsule.
+fn capsule(radius: f32, length: f32, sides: usize, cap_rings: usize) -> ShapeData {
+    let mut shape_data = ShapeData::new();
+    let height = length + 2.0 * radius;
+
+    let point = |latitude: f32, offset: f32| {
+        let (sin, cos) = latitude.sin_cos();
+        let y = sin * radius + offset;
+        let v = if height > 0.0 { y / height + 0.5 } else { 0.5 };
+
+        // See uv_sphere() for why the radius can't drop below 0.
+        ProfilePoint { radius: (cos * radius).max(0.0), y, normal: [cos, sin], v }
+    };
+    let latitudes = |from: f32| (0..=cap_rings).map(move |k| from + k as f32 / cap_rings as f32 * FRAC_PI_2);
+
+    shape_data.begin_group("top");
+    let top: Vec<ProfilePoint> = latitudes(0.0).map(|latitude| point(latitude, length / 2.0)).collect();
+    revolve(&mut shape_data, sides, &top);
+
+    shape_data.begin_group("side");
+    revolve(&mut shape_data, sides, &[point(0.0, -length / 2.0), point(0.0, length / 2.0)]);
+
+    shape_data.begin_group("bottom");
+    let bottom: Vec<ProfilePoint> = latitudes(-FRAC_PI_2).map(|latitude| point(latitude, -length / 2.0)).collect();
+    revolve(&mut shape_data, sides, &bottom);
+
+    shape_data
+}
 
-        temp_vec.push(((i + 1) as f32 * step).cos() * radius);
-        temp_vec.push(-0.5                                  );
-        temp_vec.push(((i + 1) as f32 * step).sin() * radius);
-            
-        temp_vec.push(0.0                                   );
-        temp_vec.push(-0.5                                  );
-        temp_vec.push(0.0                                   );
+// ==== PLATONIC SOLIDS
+
+// A corner of a face of a platonic solid: the position and the UV coordinate.
+// The faces are counter clockwise when seen from the outside, like the sides of the cube, and all corners lie on the sphere with a radius of 1.
+type Corner = ([f32; 3], [f32; 2]);
+
+// Returns the ShapeData of a platonic solid as a single group. When spherical is true the solid is pushed onto the sphere with smooth normals, otherwise every face stays flat.
+fn platonic_solid(name: &str, faces: &[[Corner; 3]], subdivisions: usize, spherical: bool) -> ShapeData {
+    let mut shape_data = ShapeData::new();
+    shape_data.begin_group(name);
+
+    for [a, b, c] in faces {
+        subdivided_triangle(&mut shape_data, [a.0, b.0, c.0], [a.1, b.1, c.1], subdivisions, spherical);
     }
 
-    return temp_vec;
+    shape_data
 }
 
-// ==== TUBE
+// The tetrahedron stands on a face with the fourth vertex at the top. The net is a triangle where the bottom face fills the middle and the sides fold up from its edges, so the top vertex lies on all three corners of the net.
+fn tetrahedron_faces() -> Vec<[Corner; 3]> {
+    let base = |k: usize| {
+        let (sin, cos) = (k as f32 * 2.0 * PI / 3.0).sin_cos();
+        [cos * 8.0f32.sqrt() / 3.0, -1.0 / 3.0, sin * 8.0f32.sqrt() / 3.0]
+    };
+    let top = [0.0, 1.0, 0.0];
+    let height = 3.0f32.sqrt() / 2.0;
+    let (middle_0, middle_1, middle_2) = ([0.5, 0.0], [0.75, height / 2.0], [0.25, height / 2.0]);
+
+    vec![
+        [(base(0), middle_0), (base(1), middle_1), (base(2), middle_2)],
+        [(top, [1.0, 0.0]), (base(1), middle_1), (base(0), middle_0)],
+        [(top, [0.5, height]), (base(2), middle_2), (base(1), middle_1)],
+        [(top, [0.0, 0.0]), (base(0), middle_0), (base(2), middle_2)],
+    ]
+}
+
+// The octahedron has a vertex at both poles and a ring of 4 vertices around the Y axis. The net is laid out like a UV sphere with 4 sides and 2 rings.
+fn octahedron_faces() -> Vec<[Corner; 3]> {
+    let around = |k: usize| {
+        let (sin, cos) = (k as f32 * FRAC_PI_2).sin_cos();
+        ([cos, 0.0, sin], [1.0 - k as f32 / 4.0, 0.5])
+    };
+
+    (0..4)
+        .flat_map(|k| {
+            let u = 1.0 - (k as f32 + 0.5) / 4.0;
+            [
+                [([0.0, 1.0, 0.0], [u, 1.0]), around(k + 1), around(k)],
+                [around(k), around(k + 1), ([0.0, -1.0, 0.0], [u, 0.0])],
+            ]
+        })
+        .collect()
+}
+
+// The icosahedron stands on a vertex at each pole with two rings of 5 vertices in between, the lower ring turned by half a step.
+// In the net the upper ring lies on the second line from the top and the lower ring on the line below it, half a triangle further to the right. Every triangle is 1 / 5.5 wide and 1 / 3 high.
+fn icosahedron_faces() -> Vec<[Corner; 3]> {
+    let (y, radius) = (1.0 / 5.0f32.sqrt(), 2.0 / 5.0f32.sqrt());
+
+    // The angle runs clockwise when seen from the outside so U runs the other way.
+    let upper = |k: usize| {
+        let (sin, cos) = (k as f32 * 2.0 * PI / 5.0).sin_cos();
+        ([cos * radius, y, sin * radius], [1.0 - k as f32 / 5.5, 2.0 / 3.0])
+    };
+    let lower = |k: usize| {
+        let (sin, cos) = ((k as f32 + 0.5) * 2.0 * PI / 5.0).sin_cos();
+        ([cos * radius, -y, sin * radius], [1.0 - (k as f32 + 0.5) / 5.5, 1.0 / 3.0])
+    };
+
+    (0..5)
+        .flat_map(|k| {
+            let top = ([0.0, 1.0, 0.0], [1.0 - (k as f32 + 0.5) / 5.5, 1.0]);
+            let bottom = ([0.0, -1.0, 0.0], [1.0 - (k as f32 + 1.0) / 5.5, 0.0]);
+            [
+                [top, upper(k + 1), upper(k)],
+                [upper(k), upper(k + 1), lower(k)],
+                [lower(k), upper(k + 1), lower(k + 1)],
+                [lower(k), lower(k + 1), bottom],
+            ]
+        })
+        .collect()
+}
+
+// The dodecahedron is the dual of the icosahedron: every face of the icosahedron becomes a vertex and every vertex a face.
+// The 12 pentagons are split into 5 triangles around their center and every pentagon gets its own cell in a grid of 4 by 3 cells of the texture.
+fn dodecahedron_faces() -> Vec<[Corner; 3]> {
+    let icosahedron: Vec<[[f32; 3]; 3]> = icosahedron_faces().iter().map(|face| [face[0].0, face[1].0, face[2].0]).collect();
+
+    // The vertices of the icosahedron in the order they are first used. The net repeats vertices at its seams, and those copies can differ in the last bits.
+    let same = |a: [f32; 3], b: [f32; 3]| dot(sub(a, b), sub(a, b)) < 1e-6;
+    let mut centers: Vec<[f32; 3]> = Vec::new();
+    for &vertex in icosahedron.iter().flatten() {
+        if !centers.iter().any(|&center| same(center, vertex)) {
+            centers.push(vertex);
+        }
+    }
 
-// Returns the amount of triangles the wanted tube consists off.
-fn nr_of_tube_triangles(sides: usize) -> usize {
-    return sides * 8;
-}
-
-// Returns a Vec<f32> with all the vertices. Every trio of f32's forms a vector3.
-fn tube(sides: usize, inner_radius: f32, outer_radius: f32) -> Vec<f32> {
-    let mut temp_vec: Vec<f32> = Vec::new();
-    let step = 2.0 * PI / sides as f32;
-
-    // top ring
-    for i in 0..sides {
-        temp_vec.push((i       as f32 * step).cos() * inner_radius);
-        temp_vec.push(0.5                                         );
-        temp_vec.push((i       as f32 * step).sin() * inner_radius);
-        
-        temp_vec.push(((i + 1) as f32 * step).cos() * inner_radius);
-        temp_vec.push(0.5                                         );
-        temp_vec.push(((i + 1) as f32 * step).sin() * inner_radius);
-            
-        temp_vec.push((i       as f32 * step).cos() * outer_radius);
-        temp_vec.push(0.5                                         );
-        temp_vec.push((i       as f32 * step).sin() * outer_radius);
-        
-        temp_vec.push((i       as f32 * step).cos() * outer_radius);
-        temp_vec.push(0.5                                         );
-        temp_vec.push((i       as f32 * step).sin() * outer_radius);
-
-        temp_vec.push(((i + 1) as f32 * step).cos() * inner_radius);
-        temp_vec.push(0.5                                         );
-        temp_vec.push(((i + 1) as f32 * step).sin() * inner_radius);
-
-        temp_vec.push(((i + 1) as f32 * step).cos() * outer_radius);
-        temp_vec.push(0.5                                         );
-        temp_vec.push(((i + 1) as f32 * step).sin() * outer_radius);
-    }
-
-    // outer cylinder
-    for i in 0..sides {
-        temp_vec.push((i       as f32 * step).cos() * outer_radius);
-        temp_vec.push(0.5                                         );
-        temp_vec.push((i       as f32 * step).sin() * outer_radius);
-            
-        temp_vec.push(((i + 1) as f32 * step).cos() * outer_radius);
-        temp_vec.push(0.5                                         );
-        temp_vec.push(((i + 1) as f32 * step).sin() * outer_radius);
-
-        temp_vec.push((i       as f32 * step).cos() * outer_radius);
-        temp_vec.push(-0.5                                        );
-        temp_vec.push((i       as f32 * step).sin() * outer_radius);
-
-        temp_vec.push(((i + 1) as f32 * step).cos() * outer_radius);
-        temp_vec.push(-0.5                                        );
-        temp_vec.push(((i + 1) as f32 * step).sin() * outer_radius);
-
-        temp_vec.push((i       as f32 * step).cos() * outer_radius);
-        temp_vec.push(-0.5                                        );
-        temp_vec.push((i       as f32 * step).sin() * outer_radius);
-
-        temp_vec.push(((i + 1) as f32 * step).cos() * outer_radius);
-        temp_vec.push(0.5                                         );
-        temp_vec.push(((i + 1) as f32 * step).sin() * outer_radius);
-    }
-
-    // inner cylinder
-    for i in 0..sides {
-        temp_vec.push(((i + 1) as f32 * step).cos() * inner_radius);
-        temp_vec.push(0.5                                         );
-        temp_vec.push(((i + 1) as f32 * step).sin() * inner_radius);
-        
-        temp_vec.push((i       as f32 * step).cos() * inner_radius);
-        temp_vec.push(0.5                                         );
-        temp_vec.push((i       as f32 * step).sin() * inner_radius);
-            
-        temp_vec.push((i       as f32 * step).cos() * inner_radius);
-        temp_vec.push(-0.5                                        );
-        temp_vec.push((i       as f32 * step).sin() * inner_radius);
-        
-        temp_vec.push((i       as f32 * step).cos() * inner_radius);
-        temp_vec.push(-0.5                                        );
-        temp_vec.push((i       as f32 * step).sin() * inner_radius);
-
-        temp_vec.push(((i + 1) as f32 * step).cos() * inner_radius);
-        temp_vec.push(-0.5                                        );
-        temp_vec.push(((i + 1) as f32 * step).sin() * inner_radius);
-        
-        temp_vec.push(((i + 1) as f32 * step).cos() * inner_radius);
-        temp_vec.push(0.5                                         );
-        temp_vec.push(((i + 1) as f32 * step).sin() * inner_radius);
-    }
-
-    // bottom ring
-    for i in 0..sides {
-        temp_vec.push(((i + 1) as f32 * step).cos() * inner_radius);
-        temp_vec.push(-0.5                                        );
-        temp_vec.push(((i + 1) as f32 * step).sin() * inner_radius);
-            
-        temp_vec.push((i       as f32 * step).cos() * inner_radius);
-        temp_vec.push(-0.5                                        );
-        temp_vec.push((i       as f32 * step).sin() * inner_radius);
-            
-        temp_vec.push((i       as f32 * step).cos() * outer_radius);
-        temp_vec.push(-0.5                                        );
-        temp_vec.push((i       as f32 * step).sin() * outer_radius);
-
-        temp_vec.push(((i + 1) as f32 * step).cos() * inner_radius);
-        temp_vec.push(-0.5                                        );
-        temp_vec.push(((i + 1) as f32 * step).sin() * inner_radius);
-
-        temp_vec.push((i       as f32 * step).cos() * outer_radius);
-        temp_vec.push(-0.5                                        );
-        temp_vec.push((i       as f32 * step).sin() * outer_radius);
-
-        temp_vec.push(((i + 1) as f32 * step).cos() * outer_radius);
-        temp_vec.push(-0.5                                        );
-        temp_vec.push(((i + 1) as f32 * step).sin() * outer_radius);
-    }
-
-    return temp_vec;
-}
\ No newline at end of file
+    let mut faces = Vec::new();
+    for (cell, &center) in centers.iter().enumerate() {
+        // The corners of the pentagon are the centers of the 5 faces around the vertex, sorted counter clockwise around it when seen from the outside.
+        let mut corners: Vec<[f32; 3]> = icosahedron.iter()
+            .filter(|face| face.iter().any(|&vertex| same(vertex, center)))
+            .map(|face| normalize([0, 1, 2].map(|k| face[0][k] + face[1][k] + face[2][k])))
+            .collect();
+        let first = normalize(sub(corners[0], scale(center, dot(corners[0], center))));
+        let second = cross(center, first);
+        corners.sort_by(|a, b| {
+            let angle = |p: [f32; 3]| dot(p, second).atan2(dot(p, first));
+            angle(*a).total_cmp(&angle(*b))
+        });
+
+        let middle = [0, 1, 2].map(|k| corners.iter().map(|corner| corner[k]).sum::<f32>() / 5.0);
+        let (column, row) = ((cell % 4) as f32, (cell / 4) as f32);
+        let uv = |j: usize| {
+            let (sin, cos) = (FRAC_PI_2 + j as f32 * 2.0 * PI / 5.0).sin_cos();
+            [(column + 0.5 + cos * 0.45) / 4.0, (row + 0.5 + sin * 0.45) / 3.0]
+        };
+
+        for j in 0..5 {
+            faces.push([
+                (middle, [(column + 0.5) / 4.0, (row + 0.5) / 3.0]),
+                (corners[j], uv(j)),
+                (corners[(j + 1) % 5], uv(j + 1)),
+            ]);
+        }
+    }
+
+    faces
+}
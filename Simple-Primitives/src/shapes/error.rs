@@ -0,0 +1,99 @@
+/* ==== Errors ====
+
+    Everything JS passes to the generate and export functions is checked before a shape is made, so a malformed call returns a ShapeError instead of aborting the wasm module.
+    JS receives the error as a thrown Error object with its name set to "ShapeError" and a kind property that holds the name of the variant, for example:
+
+        try { wasm._export_shape( ... ) } catch (error) { if (error.kind === 'MissingArgument') { ... } }
+
+  =================== */
+
+use std::fmt;
+use wasm_bindgen::prelude::*;
+
+// The reasons a shape can't be made. The &'static str holds the name of the argument, the f32 the value that was passed. An invalid profile, polygon, path or heightmap holds what is wrong with it instead.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShapeError {
+    UnknownShape(usize),
+    MissingArgument(&'static str),
+    InvalidCount { name: &'static str, value: f32, min: f32, max: f32 },
+    InvalidRadius(&'static str, f32),
+    InvalidLength(&'static str, f32),
+    InvalidAngle { name: &'static str, value: f32, min: f32, max: f32 },
+    InvalidChoice(&'static str, f32),
+    InnerRadiusTooLarge { inner: f32, outer: f32 },
+    TruncationTooLarge { truncation: f32, height: f32 },
+    InvalidProfile(&'static str),
+    InvalidPolygon { name: &'static str, reason: &'static str },
+    InvalidPath(&'static str),
+    InvalidHeightmap(&'static str),
+    TooManyVertices { vertices: usize, max: usize },
+    InvalidScale(f32),
+    EmptyShape,
+}
+
+impl ShapeError {
+    // The name of the variant, which JS can use to tell the errors apart.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ShapeError::UnknownShape(_) => "UnknownShape",
+            ShapeError::MissingArgument(_) => "MissingArgument",
+            ShapeError::InvalidCount { .. } => "InvalidCount",
+            ShapeError::InvalidRadius(_, _) => "InvalidRadius",
+            ShapeError::InvalidLength(_, _) => "InvalidLength",
+            ShapeError::InvalidAngle { .. } => "InvalidAngle",
+            ShapeError::InvalidChoice(_, _) => "InvalidChoice",
+            ShapeError::InnerRadiusTooLarge { .. } => "InnerRadiusTooLarge",
+            ShapeError::TruncationTooLarge { .. } => "TruncationTooLarge",
+            ShapeError::InvalidProfile(_) => "InvalidProfile",
+            ShapeError::InvalidPolygon { .. } => "InvalidPolygon",
+            ShapeError::InvalidPath(_) => "InvalidPath",
+            ShapeError::InvalidHeightmap(_) => "InvalidHeightmap",
+            ShapeError::TooManyVertices { .. } => "TooManyVertices",
+            ShapeError::InvalidScale(_) => "InvalidScale",
+            ShapeError::EmptyShape => "EmptyShape",
+        }
+    }
+}
+
+impl fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShapeError::UnknownShape(index) => write!(f, "there is no shape with index {}", index),
+            ShapeError::MissingArgument(name) => write!(f, "the {} argument is missing", name),
+            ShapeError::InvalidCount { name, value, min, max } => write!(f, "{} must be a whole number between {} and {} but is {}", name, min, max, value),
+            ShapeError::InvalidRadius(name, value) | ShapeError::InvalidLength(name, value) => {
+                write!(f, "{} must be a finite number of at least 0 but is {}", name, value)
+            }
+            ShapeError::InvalidAngle { name, value, min, max } => write!(f, "{} must be between {} and {} degrees but is {}", name, min, max, value),
+            ShapeError::InvalidChoice(name, value) => write!(f, "{} has no option with index {}", name, value),
+            ShapeError::InnerRadiusTooLarge { inner, outer } => {
+                write!(f, "the inner radius ({}) must be smaller than the outer radius ({})", inner, outer)
+            }
+            ShapeError::TruncationTooLarge { truncation, height } => {
+                write!(f, "the truncation ({}) must be smaller than the apex height ({})", truncation, height)
+            }
+            ShapeError::InvalidProfile(reason) => write!(f, "the profile {}", reason),
+            ShapeError::InvalidPolygon { name, reason } => write!(f, "the {} {}", name, reason),
+            ShapeError::InvalidPath(reason) => write!(f, "the path {}", reason),
+            ShapeError::InvalidHeightmap(reason) => write!(f, "the heightmap {}", reason),
+            ShapeError::TooManyVertices { vertices, max } => {
+                write!(f, "the shape has too many vertices ({}), at most {} are supported", vertices, max)
+            }
+            ShapeError::InvalidScale(value) => write!(f, "the scale must be a finite number other than 0 but is {}", value),
+            ShapeError::EmptyShape => write!(f, "the shape has no triangles to export"),
+        }
+    }
+}
+
+impl std::error::Error for ShapeError {}
+
+// Turns the error into a JS Error so wasm_bindgen can throw it.
+impl From<ShapeError> for JsValue {
+    fn from(error: ShapeError) -> JsValue {
+        let js_error = js_sys::Error::new(&error.to_string());
+        js_error.set_name("ShapeError");
+        js_sys::Reflect::set(&js_error, &JsValue::from_str("kind"), &JsValue::from_str(error.kind())).unwrap();
+
+        js_error.into()
+    }
+}
@@ -0,0 +1,186 @@
+/* ==== glTF ====
+
+    glTF 2.0 files consist of a JSON document that describes the scene and a binary buffer that holds the actual data.
+    There are two flavours of glTF:
+    - Embedded      - A .gltf JSON file where the buffer is included as a base64 data uri.
+    - Binary        - A .glb file where the JSON and the buffer are stored as two chunks in one binary file.
+
+    The buffer is the same for both and holds the following, one after the other, in little endian:
+    - Positions     - 3 f32's per vertex
+    - Normals       - 3 f32's per vertex
+    - UVs           - 2 f32's per vertex
+    - Indices       - 1 u32 per index
+
+    glTF puts the origin of the UV coordinates in the top left corner of the texture while we put it in the bottom left, so V is flipped when writing the buffer.
+    The position accessor needs the bounding box of the shape, which an empty shape doesn't have, so a shape without triangles returns a ShapeError instead.
+
+  =================== */
+
+use super::{ShapeData, ShapeError};
+
+// Constants from the glTF specification.
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const FLOAT: u32 = 5126;
+const UNSIGNED_INT: u32 = 5125;
+const TRIANGLES: u32 = 4;
+
+const GLB_MAGIC: u32 = 0x4654_6C67;
+const GLB_CHUNK_JSON: u32 = 0x4E4F_534A;
+const GLB_CHUNK_BIN: u32 = 0x004E_4942;
+
+// Returns the shape data as a .gltf file with the buffer embedded as a base64 data uri. The name is given to the node and the mesh.
+pub fn gltf_embedded(shape_data: &ShapeData, name: &str) -> Result<String, ShapeError> {
+    check_not_empty(shape_data)?;
+    let buffer = buffer(shape_data);
+    let uri = format!("data:application/octet-stream;base64,{}", base64(&buffer));
+
+    Ok(json(shape_data, name, buffer.len(), Some(&uri)))
+}
+
+// Returns the shape data as a .glb file. The name is given to the node and the mesh.
+pub fn glb(shape_data: &ShapeData, name: &str) -> Result<Vec<u8>, ShapeError> {
+    check_not_empty(shape_data)?;
+    let mut buffer = buffer(shape_data);
+    let mut json = json(shape_data, name, buffer.len(), None).into_bytes();
+
+    // Both chunks need to be aligned to 4 bytes. The JSON chunk is padded with spaces and the binary chunk with zeros.
+    while json.len() % 4 != 0 {
+        json.push(b' ');
+    }
+    while buffer.len() % 4 != 0 {
+        buffer.push(0);
+    }
+
+    let length = 12 + 8 + json.len() + 8 + buffer.len();
+    let mut data: Vec<u8> = Vec::with_capacity(length);
+
+    // Header
+    data.extend_from_slice(&GLB_MAGIC.to_le_bytes());
+    data.extend_from_slice(&2u32.to_le_bytes());
+    data.extend_from_slice(&(length as u32).to_le_bytes());
+
+    // JSON chunk
+    data.extend_from_slice(&(json.len() as u32).to_le_bytes());
+    data.extend_from_slice(&GLB_CHUNK_JSON.to_le_bytes());
+    data.extend_from_slice(&json);
+
+    // Binary chunk
+    data.extend_from_slice(&(buffer.len() as u32).to_le_bytes());
+    data.extend_from_slice(&GLB_CHUNK_BIN.to_le_bytes());
+    data.extend_from_slice(&buffer);
+
+    Ok(data)
+}
+
+fn check_not_empty(shape_data: &ShapeData) -> Result<(), ShapeError> {
+    if shape_data.indices.is_empty() {
+        Err(ShapeError::EmptyShape)
+    } else {
+        Ok(())
+    }
+}
+
+// Returns the positions, normals, UVs and indices as one little endian buffer.
+fn buffer(shape_data: &ShapeData) -> Vec<u8> {
+    let mut buffer: Vec<u8> = Vec::new();
+
+    for value in shape_data.vertices.iter().chain(shape_data.normals.iter()) {
+        buffer.extend_from_slice(&value.to_le_bytes());
+    }
+    for uv in shape_data.uvs.chunks(2) {
+        buffer.extend_from_slice(&uv[0].to_le_bytes());
+        buffer.extend_from_slice(&(1.0 - uv[1]).to_le_bytes());
+    }
+    for index in shape_data.indices.iter() {
+        buffer.extend_from_slice(&index.to_le_bytes());
+    }
+
+    buffer
+}
+
+// Returns the glTF JSON document. The uri of the buffer is left out for .glb files because the buffer is stored in the binary chunk.
+fn json(shape_data: &ShapeData, name: &str, buffer_length: usize, uri: Option<&str>) -> String {
+    let nr_of_vertices = shape_data.nr_of_vertices();
+    let positions_length = nr_of_vertices * 3 * 4;
+    let normals_length = nr_of_vertices * 3 * 4;
+    let uvs_length = nr_of_vertices * 2 * 4;
+    let indices_length = shape_data.indices.len() * 4;
+
+    // The position accessor needs the bounding box of the shape.
+    let mut min = [f32::MAX; 3];
+    let mut max = [f32::MIN; 3];
+    for vertex in shape_data.vertices.chunks(3) {
+        for i in 0..3 {
+            min[i] = min[i].min(vertex[i]);
+            max[i] = max[i].max(vertex[i]);
+        }
+    }
+
+    // The name is a JSON string, so quotes and backslashes need to be escaped.
+    let name = name.replace('\\', "\\\\").replace('"', "\\\"");
+
+    let buffer = match uri {
+        Some(uri) => format!(r#"{{"byteLength":{},"uri":"{}"}}"#, buffer_length, uri),
+        None => format!(r#"{{"byteLength":{}}}"#, buffer_length),
+    };
+
+    let mut json = String::new();
+    json.push('{');
+    json.push_str(r#""asset":{"version":"2.0","generator":"Simple Primitives"},"#);
+    json.push_str(r#""scene":0,"scenes":[{"nodes":[0]}],"#);
+    json.push_str(&format!(r#""nodes":[{{"mesh":0,"name":"{}"}}],"#, name));
+    json.push_str(&format!(r#""meshes":[{{"name":"{}","primitives":"#, name));
+    json.push_str(r#"[{"attributes":{"POSITION":0,"NORMAL":1,"TEXCOORD_0":2},"indices":3,"material":0,"mode":"#);
+    json.push_str(&TRIANGLES.to_string());
+    json.push_str("}]}],");
+    json.push_str(r#""materials":[{"name":"Default","pbrMetallicRoughness":{"baseColorFactor":[0.8,0.8,0.8,1.0],"metallicFactor":0.0,"roughnessFactor":0.5}}],"#);
+    json.push_str(&format!(r#""buffers":[{}],"#, buffer));
+    json.push_str(r#""bufferViews":["#);
+    json.push_str(&format!(r#"{{"buffer":0,"byteOffset":0,"byteLength":{},"target":{}}},"#, positions_length, ARRAY_BUFFER));
+    json.push_str(&format!(r#"{{"buffer":0,"byteOffset":{},"byteLength":{},"target":{}}},"#, positions_length, normals_length, ARRAY_BUFFER));
+    json.push_str(&format!(r#"{{"buffer":0,"byteOffset":{},"byteLength":{},"target":{}}},"#, positions_length + normals_length, uvs_length, ARRAY_BUFFER));
+    json.push_str(&format!(r#"{{"buffer":0,"byteOffset":{},"byteLength":{},"target":{}}}"#, positions_length + normals_length + uvs_length, indices_length, ELEMENT_ARRAY_BUFFER));
+    json.push_str("],");
+    json.push_str(r#""accessors":["#);
+    json.push_str(&format!(
+        r#"{{"bufferView":0,"componentType":{},"count":{},"type":"VEC3","min":[{},{},{}],"max":[{},{},{}]}},"#,
+        FLOAT, nr_of_vertices, min[0], min[1], min[2], max[0], max[1], max[2]
+    ));
+    json.push_str(&format!(r#"{{"bufferView":1,"componentType":{},"count":{},"type":"VEC3"}},"#, FLOAT, nr_of_vertices));
+    json.push_str(&format!(r#"{{"bufferView":2,"componentType":{},"count":{},"type":"VEC2"}},"#, FLOAT, nr_of_vertices));
+    json.push_str(&format!(r#"{{"bufferView":3,"componentType":{},"count":{},"type":"SCALAR"}}"#, UNSIGNED_INT, shape_data.indices.len()));
+    json.push(']');
+    json.push('}');
+
+    json
+}
+
+// Returns the bytes as a base64 string with padding.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    // Every 3 bytes are split into 4 groups of 6 bits, each group is one character.
+    for chunk in bytes.chunks(3) {
+        let b0 = chunk[0] as u32;
+        let b1 = *chunk.get(1).unwrap_or(&0) as u32;
+        let b2 = *chunk.get(2).unwrap_or(&0) as u32;
+        let group = (b0 << 16) | (b1 << 8) | b2;
+
+        encoded.push(ALPHABET[(group >> 18) as usize & 63] as char);
+        encoded.push(ALPHABET[(group >> 12) as usize & 63] as char);
+        if chunk.len() > 1 {
+            encoded.push(ALPHABET[(group >> 6) as usize & 63] as char);
+        } else {
+            encoded.push('=');
+        }
+        if chunk.len() > 2 {
+            encoded.push(ALPHABET[group as usize & 63] as char);
+        } else {
+            encoded.push('=');
+        }
+    }
+
+    encoded
+}
@@ -0,0 +1,133 @@
+/* ==== Noise ====
+
+    Gradient noise as described by Ken Perlin in "Improving Noise", in 2D for the terrain and in 3D for displacement. Space is split into a grid of cells and every corner of the grid gets a pseudo random gradient.
+    The noise at a point blends the dot products of the gradients of the surrounding corners with the offsets to those corners, which gives a smooth random value of about -1 to 1 that is 0 at every corner.
+    The gradients are picked from a permutation of the numbers 0 to 255, which is shuffled with the seed. The same seed always gives the same permutation and so the same noise, on every platform.
+
+    Fractal noise adds octaves of noise on top of each other. Every octave the frequency is multiplied by the lacunarity and the amplitude by the gain, so the finer details get smaller.
+
+  =================== */
+
+// The noise of one seed.
+pub struct Noise {
+    permutation: [u8; 512],
+}
+
+impl Noise {
+    // Shuffles the permutation with SplitMix64, a small pseudo random generator, so the noise only depends on the seed.
+    pub fn new(seed: u64) -> Noise {
+        let mut state = seed;
+        let mut random = || {
+            state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        };
+
+        let mut shuffled: [u8; 256] = std::array::from_fn(|i| i as u8);
+        for i in (1..256).rev() {
+            let j = (random() % (i as u64 + 1)) as usize;
+            shuffled.swap(i, j);
+        }
+
+        // The permutation is repeated so the hash of a corner never has to wrap around.
+        Noise { permutation: std::array::from_fn(|i| shuffled[i % 256]) }
+    }
+
+    fn hash(&self, i: usize) -> usize {
+        self.permutation[i] as usize
+    }
+
+    // Returns the noise at the point in the plane, which lies between about -1 and 1.
+    pub fn get_2d(&self, x: f32, y: f32) -> f32 {
+        let (cell_x, cell_y) = (x.floor(), y.floor());
+        let (i, j) = (wrap(cell_x), wrap(cell_y));
+        let (x, y) = (x - cell_x, y - cell_y);
+
+        let corner = |di: usize, dj: usize| gradient_2d(self.hash(self.hash(i + di) + j + dj), x - di as f32, y - dj as f32);
+        let (u, v) = (fade(x), fade(y));
+
+        lerp(v, lerp(u, corner(0, 0), corner(1, 0)), lerp(u, corner(0, 1), corner(1, 1)))
+    }
+
+    // Returns the noise at the point in space, which lies between about -1 and 1.
+    pub fn get_3d(&self, x: f32, y: f32, z: f32) -> f32 {
+        let (cell_x, cell_y, cell_z) = (x.floor(), y.floor(), z.floor());
+        let (i, j, k) = (wrap(cell_x), wrap(cell_y), wrap(cell_z));
+        let (x, y, z) = (x - cell_x, y - cell_y, z - cell_z);
+
+        let corner = |di: usize, dj: usize, dk: usize| {
+            gradient_3d(self.hash(self.hash(self.hash(i + di) + j + dj) + k + dk), x - di as f32, y - dj as f32, z - dk as f32)
+        };
+        let (u, v, w) = (fade(x), fade(y), fade(z));
+
+        let bottom = lerp(v, lerp(u, corner(0, 0, 0), corner(1, 0, 0)), lerp(u, corner(0, 1, 0), corner(1, 1, 0)));
+        let top = lerp(v, lerp(u, corner(0, 0, 1), corner(1, 0, 1)), lerp(u, corner(0, 1, 1), corner(1, 1, 1)));
+        lerp(w, bottom, top)
+    }
+
+    // Returns the sum of the octaves of noise at the point in the plane, divided by the sum of their amplitudes so it lies between about -1 and 1 as well.
+    pub fn fractal_2d(&self, x: f32, y: f32, octaves: usize, lacunarity: f32, gain: f32) -> f32 {
+        fractal(octaves, lacunarity, gain, |frequency| self.get_2d(x * frequency, y * frequency))
+    }
+
+    // Returns the sum of the octaves of noise at the point in space, see fractal_2d().
+    pub fn fractal_3d(&self, x: f32, y: f32, z: f32, octaves: usize, lacunarity: f32, gain: f32) -> f32 {
+        fractal(octaves, lacunarity, gain, |frequency| self.get_3d(x * frequency, y * frequency, z * frequency))
+    }
+}
+
+// Adds up the octaves, the noise function gets the frequency of the octave.
+fn fractal<F>(octaves: usize, lacunarity: f32, gain: f32, noise: F) -> f32
+where
+    F: Fn(f32) -> f32,
+{
+    let (mut sum, mut total, mut frequency, mut amplitude) = (0.0, 0.0, 1.0, 1.0);
+
+    for _ in 0..octaves {
+        sum += amplitude * noise(frequency);
+        total += amplitude;
+        frequency *= lacunarity;
+        amplitude *= gain;
+    }
+
+    if total > 0.0 { sum / total } else { 0.0 }
+}
+
+// The noise repeats every 256 cells, so the corner of a cell is looked up by its position modulo 256.
+fn wrap(cell: f32) -> usize {
+    (cell as i64 & 255) as usize
+}
+
+// Eases the position within a cell so the noise is smooth where two cells meet.
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(t: f32, a: f32, b: f32) -> f32 {
+    a + t * (b - a)
+}
+
+// The dot product of the offset with one of 8 gradients, picked by the hash: the 4 axes and the 4 diagonals.
+fn gradient_2d(hash: usize, x: f32, y: f32) -> f32 {
+    match hash & 7 {
+        0 => x + y,
+        1 => -x + y,
+        2 => x - y,
+        3 => -x - y,
+        4 => x,
+        5 => -x,
+        6 => y,
+        _ => -y,
+    }
+}
+
+// The dot product of the offset with one of the 12 gradients that point from the center of a cube to the middle of its edges, picked by the hash. Four of them are repeated to get 16 options.
+fn gradient_3d(hash: usize, x: f32, y: f32, z: f32) -> f32 {
+    let h = hash & 15;
+    let u = if h < 8 { x } else { y };
+    let v = if h < 4 { y } else if h == 12 || h == 14 { x } else { z };
+
+    (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
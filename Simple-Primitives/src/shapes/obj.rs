@@ -0,0 +1,357 @@
+/* ==== OBJ ====
+
+    OBJ files are text files with a line per element:
+    - o             - The name of the object.
+    - g             - The name of a group of faces, like the top of a cylinder.
+    - v, vt, vn     - A position, UV coordinate or normal.
+    - f             - A triangle as three v/vt/vn index trios. The indices start at 1.
+    - mtllib        - The .mtl file that holds the materials.
+    - usemtl        - The material of the faces that follow.
+
+    Positions, UV coordinates and normals each get their own list, so they are deduplicated separately. A cube for example only needs 8 positions and 6 normals.
+    Values are compared after they are rounded to the precision, so values that would be written the same are written only once.
+
+    OBJ files can also be read back into a ShapeData with parse_obj. Every unique v/vt/vn trio becomes one vertex.
+    - Faces with more than three corners are split into triangles, see triangulate.rs.
+    - Negative indices count back from the last element that was read, so -1 is the latest one.
+    - Corners without a normal get the average normal of the faces around them, corners without a UV coordinate get 0, 0.
+    - Every o and g line starts a new group.
+    - Other elements like lines, materials and smoothing groups are skipped.
+
+  =================== */
+
+use super::triangulate::triangulate;
+use super::{face_normal, normalize, ShapeData};
+use std::collections::HashMap;
+use std::fmt;
+use wasm_bindgen::prelude::*;
+
+// The name of the material in the .mtl file that the OBJ file uses.
+pub const MATERIAL_NAME: &str = "Default";
+
+// The options of the OBJ export.
+// - name           - The name of the object.
+// - precision      - The amount of decimals every value is written with.
+// - material_file  - The name of the .mtl file, when it is given the OBJ file references it and uses its material.
+pub struct ObjOptions {
+    pub name: String,
+    pub precision: usize,
+    pub material_file: Option<String>,
+}
+
+impl Default for ObjOptions {
+    fn default() -> Self {
+        ObjOptions {
+            name: String::from("Shape"),
+            precision: 6,
+            material_file: None,
+        }
+    }
+}
+
+// A list of values that only holds every written value once.
+struct UniqueLines {
+    lines: String,
+    indices: HashMap<String, usize>,
+}
+
+impl UniqueLines {
+    fn new() -> UniqueLines {
+        UniqueLines {
+            lines: String::new(),
+            indices: HashMap::new(),
+        }
+    }
+
+    // Writes the values with the prefix if they weren't written before and returns their OBJ index.
+    fn push(&mut self, prefix: &str, values: &[f32], precision: usize) -> usize {
+        let mut line = String::from(prefix);
+        for value in values {
+            line.push_str(&format!(" {:.*}", precision, value));
+        }
+
+        let next = self.indices.len() + 1;
+        let index = *self.indices.entry(line.clone()).or_insert(next);
+        if index == next {
+            self.lines.push_str(&line);
+            self.lines.push('\n');
+        }
+
+        index
+    }
+}
+
+// Returns the shape data as an OBJ file.
+pub fn obj(shape_data: &ShapeData, options: &ObjOptions) -> String {
+    let precision = options.precision;
+    let mut positions = UniqueLines::new();
+    let mut uvs = UniqueLines::new();
+    let mut normals = UniqueLines::new();
+
+    // The OBJ index trio of every vertex.
+    let mut vertices: Vec<[usize; 3]> = Vec::with_capacity(shape_data.nr_of_vertices());
+    for i in 0..shape_data.nr_of_vertices() {
+        vertices.push([
+            positions.push("v", &shape_data.vertices[i * 3..i * 3 + 3], precision),
+            uvs.push("vt", &shape_data.uvs[i * 2..i * 2 + 2], precision),
+            normals.push("vn", &shape_data.normals[i * 3..i * 3 + 3], precision),
+        ]);
+    }
+
+    let mut data = String::new();
+    data.push_str("# Simple Primitives\n");
+    if let Some(material_file) = &options.material_file {
+        data.push_str(&format!("mtllib {}\n", material_file));
+    }
+    data.push_str(&format!("o {}\n", options.name));
+    data.push_str(&positions.lines);
+    data.push_str(&uvs.lines);
+    data.push_str(&normals.lines);
+    if options.material_file.is_some() {
+        data.push_str(&format!("usemtl {}\n", MATERIAL_NAME));
+    }
+
+    // Triangles that are not part of a group are written before the first group.
+    let first_group = shape_data.groups.first().map_or(shape_data.nr_of_triangles(), |group| group.start);
+    write_faces(&mut data, shape_data, &vertices, 0..first_group);
+
+    for group in shape_data.groups.iter() {
+        data.push_str(&format!("g {}\n", group.name));
+        write_faces(&mut data, shape_data, &vertices, group.start..group.start + group.count);
+    }
+
+    data
+}
+
+// Writes a face line for every triangle in the range.
+fn write_faces(data: &mut String, shape_data: &ShapeData, vertices: &[[usize; 3]], triangles: std::ops::Range<usize>) {
+    for triangle in shape_data.indices[triangles.start * 3..triangles.end * 3].chunks(3) {
+        data.push('f');
+        for &index in triangle {
+            let [v, vt, vn] = vertices[index as usize];
+            data.push_str(&format!(" {}/{}/{}", v, vt, vn));
+        }
+        data.push('\n');
+    }
+}
+
+// Returns the .mtl file with the material the OBJ file uses. It is the same light gray material as the glTF export.
+pub fn mtl() -> String {
+    let mut data = String::new();
+
+    data.push_str("# Simple Primitives\n");
+    data.push_str(&format!("newmtl {}\n", MATERIAL_NAME));
+    data.push_str("Ka 0.0 0.0 0.0\n");
+    data.push_str("Kd 0.8 0.8 0.8\n");
+    data.push_str("Ks 0.5 0.5 0.5\n");
+    data.push_str("Ns 100.0\n");
+    data.push_str("d 1.0\n");
+    data.push_str("illum 2\n");
+
+    data
+}
+
+// An error in an OBJ file. The line starts at 1.
+#[derive(Debug)]
+pub struct ObjError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ObjError {}
+
+// Turns the error into a JS Error with its name set to "ObjError" and a line property, just like ShapeError does.
+impl From<ObjError> for JsValue {
+    fn from(error: ObjError) -> JsValue {
+        let js_error = js_sys::Error::new(&error.to_string());
+        js_error.set_name("ObjError");
+        js_sys::Reflect::set(&js_error, &JsValue::from_str("line"), &JsValue::from(error.line as u32)).unwrap();
+
+        js_error.into()
+    }
+}
+
+// Reads the numbers after the keyword of a line. At least min numbers are read, any numbers after max are skipped.
+fn parse_numbers(parts: &[&str], min: usize, max: usize, line: usize) -> Result<Vec<f32>, ObjError> {
+    if parts.len() < min {
+        return Err(ObjError {
+            line,
+            message: format!("expected at least {} numbers but found {}", min, parts.len()),
+        });
+    }
+
+    parts.iter().take(max).map(|part| {
+        part.parse::<f32>().map_err(|_| ObjError {
+            line,
+            message: format!("'{}' is not a number", part),
+        })
+    }).collect()
+}
+
+// Turns an OBJ index, which starts at 1 or counts back from the end when it is negative, into an index that starts at 0.
+fn resolve_index(part: &str, count: usize, kind: &str, line: usize) -> Result<usize, ObjError> {
+    let index: i64 = part.parse().map_err(|_| ObjError {
+        line,
+        message: format!("'{}' is not a valid {} index", part, kind),
+    })?;
+
+    let resolved = if index > 0 { index - 1 } else { count as i64 + index };
+    if index == 0 || resolved < 0 || resolved >= count as i64 {
+        return Err(ObjError {
+            line,
+            message: format!("{} index {} is out of range, there are {} {}s", kind, index, count, kind),
+        });
+    }
+
+    Ok(resolved as usize)
+}
+
+// Reads an OBJ file into a ShapeData.
+pub fn parse_obj(text: &str) -> Result<ShapeData, ObjError> {
+    let mut shape_data = ShapeData::new();
+    let mut positions: Vec<[f32; 3]> = Vec::new();
+    let mut uvs: Vec<[f32; 2]> = Vec::new();
+    let mut normals: Vec<[f32; 3]> = Vec::new();
+
+    // The vertex of every v/vt/vn trio we have seen, and the vertices that need a normal calculated from their faces.
+    let mut corners: HashMap<(usize, Option<usize>, Option<usize>), u32> = HashMap::new();
+    let mut missing_normals: Vec<bool> = Vec::new();
+
+    for (i, content) in text.lines().enumerate() {
+        let line = i + 1;
+        let content = content.split('#').next().unwrap_or("");
+        let parts: Vec<&str> = content.split_whitespace().collect();
+        if parts.is_empty() {
+            continue;
+        }
+
+        match parts[0] {
+            "v" => {
+                let values = parse_numbers(&parts[1..], 3, 3, line)?;
+                positions.push([values[0], values[1], values[2]]);
+            }
+            "vt" => {
+                let values = parse_numbers(&parts[1..], 1, 2, line)?;
+                uvs.push([values[0], *values.get(1).unwrap_or(&0.0)]);
+            }
+            "vn" => {
+                let values = parse_numbers(&parts[1..], 3, 3, line)?;
+                normals.push(normalize([values[0], values[1], values[2]]));
+            }
+            "o" | "g" => {
+                let name = if parts.len() > 1 { parts[1..].join(" ") } else { String::from("default") };
+                shape_data.begin_group(&name);
+            }
+            "f" => {
+                if parts.len() < 4 {
+                    return Err(ObjError {
+                        line,
+                        message: format!("a face needs at least 3 corners but has {}", parts.len() - 1),
+                    });
+                }
+
+                // Every corner is v, v/vt, v//vn or v/vt/vn.
+                let mut face: Vec<u32> = Vec::new();
+                for corner in parts[1..].iter() {
+                    let mut indices = corner.split('/');
+                    let v = resolve_index(indices.next().unwrap_or(""), positions.len(), "position", line)?;
+                    let vt = match indices.next() {
+                        Some(part) if !part.is_empty() => Some(resolve_index(part, uvs.len(), "uv", line)?),
+                        _ => None,
+                    };
+                    let vn = match indices.next() {
+                        Some(part) if !part.is_empty() => Some(resolve_index(part, normals.len(), "normal", line)?),
+                        _ => None,
+                    };
+
+                    let index = match corners.get(&(v, vt, vn)) {
+                        Some(&index) => index,
+                        None => {
+                            let index = shape_data.push_vertex(
+                                positions[v],
+                                vn.map_or([0.0, 0.0, 0.0], |vn| normals[vn]),
+                                vt.map_or([0.0, 0.0], |vt| uvs[vt]),
+                            );
+                            corners.insert((v, vt, vn), index);
+                            missing_normals.push(vn.is_none());
+                            index
+                        }
+                    };
+                    face.push(index);
+                }
+
+                for [a, b, c] in triangulate_face(&shape_data, &face) {
+                    shape_data.push_triangle(a, b, c);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Corners without a normal get the sum of the normals of the faces they are part of, which is normalized afterwards.
+    for triangle in shape_data.indices.clone().chunks(3) {
+        let normal = face_normal(
+            shape_data.position(triangle[0]),
+            shape_data.position(triangle[1]),
+            shape_data.position(triangle[2]),
+        );
+
+        for &index in triangle {
+            if missing_normals[index as usize] {
+                let i = index as usize * 3;
+                for (sum, value) in shape_data.normals[i..i + 3].iter_mut().zip(normal.iter()) {
+                    *sum += value;
+                }
+            }
+        }
+    }
+    for (normal, &missing) in shape_data.normals.chunks_mut(3).zip(missing_normals.iter()) {
+        if missing {
+            let normalized = normalize([normal[0], normal[1], normal[2]]);
+            normal.copy_from_slice(&normalized);
+        }
+    }
+
+    // An o line that is directly followed by a g line leaves an empty group behind.
+    shape_data.groups.retain(|group| group.count > 0);
+
+    Ok(shape_data)
+}
+
+// Splits a face into triangles that keep the winding of the face.
+fn triangulate_face(shape_data: &ShapeData, face: &[u32]) -> Vec<[u32; 3]> {
+    if face.len() == 3 {
+        return vec![[face[0], face[1], face[2]]];
+    }
+
+    // The face is projected on the plane of the axes where it is the largest. The normal of the face is calculated with Newell's method, which also works for concave faces.
+    let points: Vec<[f32; 3]> = face.iter().map(|&index| shape_data.position(index)).collect();
+    let mut normal = [0.0f32; 3];
+    for i in 0..points.len() {
+        let a = points[i];
+        let b = points[(i + 1) % points.len()];
+        normal[0] += (a[1] - b[1]) * (a[2] + b[2]);
+        normal[1] += (a[2] - b[2]) * (a[0] + b[0]);
+        normal[2] += (a[0] - b[0]) * (a[1] + b[1]);
+    }
+
+    let (u, v) = if normal[0].abs() >= normal[1].abs() && normal[0].abs() >= normal[2].abs() {
+        (1, 2)
+    } else if normal[1].abs() >= normal[2].abs() {
+        (2, 0)
+    } else {
+        (0, 1)
+    };
+    let projected: Vec<[f32; 2]> = points.iter().map(|point| [point[u], point[v]]).collect();
+
+    triangulate(&projected)
+        .iter()
+        .map(|triangle| [face[triangle[0]], face[triangle[1]], face[triangle[2]]])
+        .collect()
+}
@@ -0,0 +1,799 @@
+/* ==== Parameters ====
+
+    Every shape has its own parameters, which are stored in the matching variant of ShapeParams so the shape functions get typed values instead of a list of numbers.
+    Shape::params() describes the parameters of a shape: the name, a label for the editor, the type, the default value and the range. This schema is passed to JS, which builds the editor from it and passes the values back as an object with a property per parameter name:
+
+        { sides: 10, radius: 1, smooth: true }
+
+    The values are checked against the schema when they are read. Counts like subdivisions and sides are rounded down and angles are in degrees, both need to lie between the min and max of the schema. The max of a count keeps the size of the mesh in check. Radii and lengths need to be finite and at least 0.
+    Rules between parameters, like the inner radius of a tube being smaller than the outer radius, are checked by ShapeParams::validate().
+
+    The profile of the lathe is a list of points instead of a number, so it isn't part of the schema. JS passes it as an extra profile property with a radius and a height for every point, the editor leaves it out to get the default profile:
+
+        { radial_segments: 24, start_angle: 0, sweep_angle: 360, smooth: true, profile: [0, -0.5, 0.3, -0.5, 0.3, 0.5] }
+
+    The extrusion works the same way with an outline property, which holds an x and a y for every corner of the polygon, and a holes property with a polygon for every hole:
+
+        { depth: 0.25, bevel_size: 0.05, bevel_segments: 3, outline: [0, 0, 1, 0, 0, 1], holes: [[0.2, 0.2, 0.4, 0.2, 0.2, 0.4]] }
+
+    The sweep takes a path property with an x, a y and a z for every point. A section property replaces the round cross-section of the sides and the radius with a polygon, and the scales and twists properties hold a scale and an angle in degrees for every point of the path:
+
+        { sides: 12, radius: 0.1, path_segments: 8, twist: 0, capped: true, smooth: true, path: [0, 0, 0, 0, 1, 0, 1, 1, 0], scales: [1, 1, 0.5] }
+
+    The terrain is shaped by fractal noise unless it gets a heightmap property with a grayscale byte for every pixel, row by row from the top, like an array, a Uint8Array or a Uint8ClampedArray.
+    The heightmap_width property holds the number of pixels in a row, it can be left out for a square heightmap:
+
+        { x_subdivisions: 64, y_subdivisions: 64, height: 0.25, seed: 1, frequency: 4, octaves: 5, lacunarity: 2, gain: 0.5, smooth: true, heightmap: [0, 128, 255, 128], heightmap_width: 2 }
+
+    Any shape can be displaced along its normals by seeded noise with a displacement object that holds a property per parameter of Displacement::params():
+
+        { sides: 10, radius: 1, smooth: true, displacement: { seed: 1, amplitude: 0.1, frequency: 2, octaves: 4 } }
+
+    To add a shape, add a variant to Shape and ShapeParams and fill in the match arms in this file, the editor picks it up from the schema.
+
+  =================== */
+
+use super::{Shape, ShapeError};
+use wasm_bindgen::prelude::*;
+
+// The type of a parameter, which tells the editor which input to show and how the value is checked.
+// - Count      - A whole number between min and max, like subdivisions or sides.
+// - Radius     - A finite number of at least 0.
+// - Length     - A finite number of at least 0, like a height.
+// - Angle      - A finite number of degrees between min and max.
+// - Choice     - One of the options, passed as the index of the option.
+// - Bool       - A checkbox, passed as 1 or 0 when the values are numbers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParamKind {
+    Count,
+    Radius,
+    Length,
+    Angle,
+    Choice(&'static [&'static str]),
+    Bool,
+}
+
+impl ParamKind {
+    // The name of the type in the schema JS receives.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ParamKind::Count => "int",
+            ParamKind::Radius | ParamKind::Length | ParamKind::Angle => "float",
+            ParamKind::Choice(_) => "choice",
+            ParamKind::Bool => "bool",
+        }
+    }
+}
+
+// The description of one parameter. Max is infinite when there is no upper limit.
+#[derive(Debug, Clone, Copy)]
+pub struct ParamInfo {
+    pub name: &'static str,
+    pub label: &'static str,
+    pub kind: ParamKind,
+    pub default: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+const SUBDIVISIONS: ParamInfo = ParamInfo { name: "subdivisions", label: "Subdivisions", kind: ParamKind::Count, default: 5.0, min: 1.0, max: 256.0 };
+const SIDES: ParamInfo = ParamInfo { name: "sides", label: "Sides", kind: ParamKind::Count, default: 10.0, min: 3.0, max: 1024.0 };
+const RADIUS: ParamInfo = ParamInfo { name: "radius", label: "Radius", kind: ParamKind::Radius, default: 1.0, min: 0.0, max: f32::INFINITY };
+const INNER_RADIUS: ParamInfo = ParamInfo { name: "inner_radius", label: "Inner Radius", kind: ParamKind::Radius, default: 0.8, min: 0.0, max: f32::INFINITY };
+const OUTER_RADIUS: ParamInfo = ParamInfo { name: "outer_radius", label: "Outer Radius", kind: ParamKind::Radius, default: 1.0, min: 0.0, max: f32::INFINITY };
+const BOTTOM_RADIUS: ParamInfo = ParamInfo { name: "bottom_radius", label: "Bottom Radius", kind: ParamKind::Radius, default: 1.0, min: 0.0, max: f32::INFINITY };
+const TOP_RADIUS: ParamInfo = ParamInfo { name: "top_radius", label: "Top Radius", kind: ParamKind::Radius, default: 0.0, min: 0.0, max: f32::INFINITY };
+const HEIGHT_SEGMENTS: ParamInfo = ParamInfo { name: "height_segments", label: "Height Segments", kind: ParamKind::Count, default: 1.0, min: 1.0, max: 1024.0 };
+const CAPPED: ParamInfo = ParamInfo { name: "capped", label: "Capped", kind: ParamKind::Bool, default: 1.0, min: 0.0, max: 1.0 };
+const MAJOR_RADIUS: ParamInfo = ParamInfo { name: "major_radius", label: "Major Radius", kind: ParamKind::Radius, default: 1.0, min: 0.0, max: f32::INFINITY };
+const MINOR_RADIUS: ParamInfo = ParamInfo { name: "minor_radius", label: "Minor Radius", kind: ParamKind::Radius, default: 0.25, min: 0.0, max: f32::INFINITY };
+const RADIAL_SEGMENTS: ParamInfo = ParamInfo { name: "radial_segments", label: "Radial Segments", kind: ParamKind::Count, default: 24.0, min: 1.0, max: 1024.0 };
+const TUBULAR_SEGMENTS: ParamInfo = ParamInfo { name: "tubular_segments", label: "Tubular Segments", kind: ParamKind::Count, default: 12.0, min: 1.0, max: 1024.0 };
+const ARC: ParamInfo = ParamInfo { name: "arc", label: "Arc", kind: ParamKind::Angle, default: 360.0, min: 0.0, max: 360.0 };
+const CAP_SEGMENTS: ParamInfo = ParamInfo { name: "cap_segments", label: "Cap Segments", kind: ParamKind::Count, default: 1.0, min: 1.0, max: 1024.0 };
+const START_ANGLE: ParamInfo = ParamInfo { name: "start_angle", label: "Start Angle", kind: ParamKind::Angle, default: 0.0, min: 0.0, max: 360.0 };
+const SWEEP_ANGLE: ParamInfo = ParamInfo { name: "sweep_angle", label: "Sweep Angle", kind: ParamKind::Angle, default: 360.0, min: 0.0, max: 360.0 };
+const SIDE_CAPS: ParamInfo = ParamInfo { name: "side_caps", label: "Side Caps", kind: ParamKind::Bool, default: 1.0, min: 0.0, max: 1.0 };
+const PRISM_SIDES: ParamInfo = ParamInfo { name: "sides", label: "Sides", kind: ParamKind::Count, default: 6.0, min: 3.0, max: 1024.0 };
+const PYRAMID_SIDES: ParamInfo = ParamInfo { name: "sides", label: "Sides", kind: ParamKind::Count, default: 4.0, min: 3.0, max: 1024.0 };
+const TWIST: ParamInfo = ParamInfo { name: "twist", label: "Twist", kind: ParamKind::Angle, default: 0.0, min: -360.0, max: 360.0 };
+const APEX_HEIGHT: ParamInfo = ParamInfo { name: "height", label: "Apex Height", kind: ParamKind::Length, default: 1.0, min: 0.0, max: f32::INFINITY };
+const TRUNCATION: ParamInfo = ParamInfo { name: "truncation", label: "Truncation", kind: ParamKind::Length, default: 0.0, min: 0.0, max: f32::INFINITY };
+const LATHE_SEGMENTS: ParamInfo = ParamInfo { name: "radial_segments", label: "Radial Segments", kind: ParamKind::Count, default: 24.0, min: 1.0, max: 1024.0 };
+const EXTRUDE_DEPTH: ParamInfo = ParamInfo { name: "depth", label: "Depth", kind: ParamKind::Length, default: 0.25, min: 0.0, max: f32::INFINITY };
+const BEVEL_SIZE: ParamInfo = ParamInfo { name: "bevel_size", label: "Bevel Size", kind: ParamKind::Length, default: 0.05, min: 0.0, max: f32::INFINITY };
+const BEVEL_SEGMENTS: ParamInfo = ParamInfo { name: "bevel_segments", label: "Bevel Segments", kind: ParamKind::Count, default: 3.0, min: 1.0, max: 64.0 };
+const SWEEP_SIDES: ParamInfo = ParamInfo { name: "sides", label: "Sides", kind: ParamKind::Count, default: 12.0, min: 3.0, max: 1024.0 };
+const SWEEP_RADIUS: ParamInfo = ParamInfo { name: "radius", label: "Radius", kind: ParamKind::Radius, default: 0.1, min: 0.0, max: f32::INFINITY };
+const PATH_SEGMENTS: ParamInfo = ParamInfo { name: "path_segments", label: "Path Segments", kind: ParamKind::Count, default: 8.0, min: 1.0, max: 256.0 };
+const X_SUBDIVISIONS: ParamInfo = ParamInfo { name: "x_subdivisions", label: "X Subdivisions", kind: ParamKind::Count, default: 64.0, min: 1.0, max: 1024.0 };
+const Y_SUBDIVISIONS: ParamInfo = ParamInfo { name: "y_subdivisions", label: "Y Subdivisions", kind: ParamKind::Count, default: 64.0, min: 1.0, max: 1024.0 };
+const TERRAIN_HEIGHT: ParamInfo = ParamInfo { name: "height", label: "Height", kind: ParamKind::Length, default: 0.25, min: 0.0, max: f32::INFINITY };
+const SEED: ParamInfo = ParamInfo { name: "seed", label: "Seed", kind: ParamKind::Count, default: 1.0, min: 1.0, max: 16777216.0 };
+const FREQUENCY: ParamInfo = ParamInfo { name: "frequency", label: "Frequency", kind: ParamKind::Length, default: 4.0, min: 0.0, max: f32::INFINITY };
+const OCTAVES: ParamInfo = ParamInfo { name: "octaves", label: "Octaves", kind: ParamKind::Count, default: 5.0, min: 1.0, max: 16.0 };
+const LACUNARITY: ParamInfo = ParamInfo { name: "lacunarity", label: "Lacunarity", kind: ParamKind::Length, default: 2.0, min: 0.0, max: f32::INFINITY };
+const GAIN: ParamInfo = ParamInfo { name: "gain", label: "Gain", kind: ParamKind::Length, default: 0.5, min: 0.0, max: f32::INFINITY };
+const AMPLITUDE: ParamInfo = ParamInfo { name: "amplitude", label: "Amplitude", kind: ParamKind::Length, default: 0.1, min: 0.0, max: f32::INFINITY };
+const DISPLACEMENT_FREQUENCY: ParamInfo = ParamInfo { name: "frequency", label: "Frequency", kind: ParamKind::Length, default: 2.0, min: 0.0, max: f32::INFINITY };
+const DISPLACEMENT_OCTAVES: ParamInfo = ParamInfo { name: "octaves", label: "Octaves", kind: ParamKind::Count, default: 4.0, min: 1.0, max: 16.0 };
+const CAPSULE_RADIUS: ParamInfo = ParamInfo { name: "radius", label: "Radius", kind: ParamKind::Radius, default: 0.5, min: 0.0, max: f32::INFINITY };
+const LENGTH: ParamInfo = ParamInfo { name: "length", label: "Length", kind: ParamKind::Length, default: 1.0, min: 0.0, max: f32::INFINITY };
+const CAP_RINGS: ParamInfo = ParamInfo { name: "cap_rings", label: "Cap Rings", kind: ParamKind::Count, default: 6.0, min: 1.0, max: 1024.0 };
+const SOLID_SUBDIVISIONS: ParamInfo = ParamInfo { name: "subdivisions", label: "Subdivisions", kind: ParamKind::Count, default: 1.0, min: 1.0, max: 256.0 };
+const SPHERICAL: ParamInfo = ParamInfo { name: "spherical", label: "Project To Sphere", kind: ParamKind::Bool, default: 0.0, min: 0.0, max: 1.0 };
+const WIDTH: ParamInfo = ParamInfo { name: "width", label: "Width", kind: ParamKind::Length, default: 1.0, min: 0.0, max: f32::INFINITY };
+const HEIGHT: ParamInfo = ParamInfo { name: "height", label: "Height", kind: ParamKind::Length, default: 1.0, min: 0.0, max: f32::INFINITY };
+const DEPTH: ParamInfo = ParamInfo { name: "depth", label: "Depth", kind: ParamKind::Length, default: 1.0, min: 0.0, max: f32::INFINITY };
+const FILLET_RADIUS: ParamInfo = ParamInfo { name: "fillet_radius", label: "Fillet Radius", kind: ParamKind::Radius, default: 0.1, min: 0.0, max: f32::INFINITY };
+const FILLET_SEGMENTS: ParamInfo = ParamInfo { name: "fillet_segments", label: "Fillet Segments", kind: ParamKind::Count, default: 4.0, min: 1.0, max: 64.0 };
+const SPHERE_MODE: ParamInfo = ParamInfo { name: "mode", label: "Mode", kind: ParamKind::Choice(&["Cube", "UV", "Ico"]), default: 0.0, min: 0.0, max: 2.0 };
+const SMOOTH: ParamInfo = ParamInfo { name: "smooth", label: "Smooth", kind: ParamKind::Bool, default: 1.0, min: 0.0, max: 1.0 };
+
+impl Shape {
+    // The parameters of the shape, in the order ShapeParams::values() returns them.
+    pub fn params(&self) -> &'static [ParamInfo] {
+        match self {
+            Shape::Plane => &[SUBDIVISIONS],
+            Shape::Disk => &[SIDES, RADIUS, CAP_SEGMENTS, START_ANGLE, SWEEP_ANGLE],
+            Shape::Cube => &[SUBDIVISIONS],
+            Shape::Sphere => &[SUBDIVISIONS, SPHERE_MODE, SMOOTH],
+            Shape::Cylinder => &[SIDES, RADIUS, HEIGHT_SEGMENTS, CAP_SEGMENTS, START_ANGLE, SWEEP_ANGLE, SIDE_CAPS, SMOOTH],
+            Shape::Tube => &[SIDES, INNER_RADIUS, OUTER_RADIUS, HEIGHT_SEGMENTS, CAP_SEGMENTS, START_ANGLE, SWEEP_ANGLE, SIDE_CAPS, SMOOTH],
+            Shape::Cone => &[SIDES, BOTTOM_RADIUS, TOP_RADIUS, HEIGHT_SEGMENTS, CAPPED, SMOOTH],
+            Shape::Torus => &[MAJOR_RADIUS, MINOR_RADIUS, RADIAL_SEGMENTS, TUBULAR_SEGMENTS, ARC, SMOOTH],
+            Shape::Capsule => &[CAPSULE_RADIUS, LENGTH, RADIAL_SEGMENTS, CAP_RINGS, SMOOTH],
+            Shape::Tetrahedron | Shape::Octahedron | Shape::Dodecahedron | Shape::Icosahedron => &[SOLID_SUBDIVISIONS, SPHERICAL],
+            Shape::RoundedBox => &[WIDTH, HEIGHT, DEPTH, FILLET_RADIUS, FILLET_SEGMENTS],
+            Shape::Ring => &[SIDES, INNER_RADIUS, OUTER_RADIUS, CAP_SEGMENTS, START_ANGLE, SWEEP_ANGLE],
+            Shape::Prism => &[PRISM_SIDES, RADIUS, HEIGHT, TWIST, HEIGHT_SEGMENTS],
+            Shape::Pyramid => &[PYRAMID_SIDES, RADIUS, APEX_HEIGHT, TRUNCATION],
+            Shape::Lathe => &[LATHE_SEGMENTS, START_ANGLE, SWEEP_ANGLE, SMOOTH],
+            Shape::Extrude => &[EXTRUDE_DEPTH, BEVEL_SIZE, BEVEL_SEGMENTS],
+            Shape::Sweep => &[SWEEP_SIDES, SWEEP_RADIUS, PATH_SEGMENTS, TWIST, CAPPED, SMOOTH],
+            Shape::Terrain => &[X_SUBDIVISIONS, Y_SUBDIVISIONS, TERRAIN_HEIGHT, SEED, FREQUENCY, OCTAVES, LACUNARITY, GAIN, SMOOTH],
+        }
+    }
+
+    // The parameters of the shape with their default values.
+    pub fn default_params(&self) -> ShapeParams {
+        let params = self.params();
+        ShapeParams::from_values(*self, |name| params.iter().find(|info| info.name == name).map(|info| info.default))
+            .expect("the defaults should be valid")
+    }
+}
+
+// The profile the lathe gets when none is given: a vase with a closed bottom, as a radius and a height for every point from the bottom to the top.
+pub const DEFAULT_PROFILE: [f32; 14] = [0.0, -0.5, 0.3, -0.5, 0.45, -0.3, 0.4, 0.0, 0.2, 0.3, 0.2, 0.4, 0.3, 0.5];
+
+// The polygon the extrusion gets when none is given: a square frame, as an x and a y for every corner.
+pub const DEFAULT_OUTLINE: [f32; 8] = [-0.5, -0.5, 0.5, -0.5, 0.5, 0.5, -0.5, 0.5];
+pub const DEFAULT_HOLE: [f32; 8] = [-0.25, -0.25, 0.25, -0.25, 0.25, 0.25, -0.25, 0.25];
+
+// The path the sweep follows when none is given: an arch, as an x, a y and a z for every point.
+pub const DEFAULT_PATH: [f32; 15] = [-0.5, -0.5, 0.0, -0.5, 0.1, 0.0, 0.0, 0.5, 0.0, 0.5, 0.1, 0.0, 0.5, -0.5, 0.0];
+
+// The noise that pushes the vertices of a shape along their normals, see ShapeData::displace(). The amplitude is the furthest a vertex moves and the frequency the number of bumps per unit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Displacement {
+    pub seed: usize,
+    pub amplitude: f32,
+    pub frequency: f32,
+    pub octaves: usize,
+}
+
+impl Displacement {
+    // The parameters of the displacement, in the order Displacement::values() returns them.
+    pub fn params() -> &'static [ParamInfo] {
+        &[SEED, AMPLITUDE, DISPLACEMENT_FREQUENCY, DISPLACEMENT_OCTAVES]
+    }
+
+    // The parameters with their default values.
+    pub fn default_params() -> Displacement {
+        let params = Displacement::params();
+        Displacement::from_values(|name| params.iter().find(|info| info.name == name).map(|info| info.default)).expect("the defaults should be valid")
+    }
+
+    pub fn values(&self) -> Vec<f32> {
+        vec![self.seed as f32, self.amplitude, self.frequency, self.octaves as f32]
+    }
+
+    // Reads the parameters by name, just like ShapeParams::from_values().
+    pub fn from_values<F>(value: F) -> Result<Displacement, ShapeError>
+    where
+        F: Fn(&'static str) -> Option<f32>,
+    {
+        let values = Displacement::params().iter()
+            .map(|info| value(info.name).ok_or(ShapeError::MissingArgument(info.name)))
+            .collect::<Result<Vec<f32>, ShapeError>>()?;
+        check_values(Displacement::params(), &values)?;
+
+        Ok(Displacement { seed: values[0] as usize, amplitude: values[1], frequency: values[2], octaves: values[3] as usize })
+    }
+}
+
+// The ways a sphere can be built, in the order of the options of the mode parameter. See sphere() for the details.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SphereMode {
+    Cube = 0,
+    Uv = 1,
+    Ico = 2,
+}
+
+impl SphereMode {
+    const ALL: [SphereMode; 3] = [SphereMode::Cube, SphereMode::Uv, SphereMode::Ico];
+}
+
+// The typed parameters of every shape. Smooth gives hard edges when it is false, see ShapeData::hard_edges(). Capped closes the ends of the shape. Angles are in degrees.
+// Spherical pushes the vertices of a platonic solid onto the sphere around it.
+// Height segments split a wall into rows and cap segments split a cap into rings around its center.
+// The start and sweep angle cut a slice out of a round shape, side caps close the cut faces of a slice so it stays watertight.
+// The profile of a lathe holds a radius and a height for every point, see lathe(). The outline and holes of an extrusion hold an x and a y for every corner, see extrude().
+// The path of a sweep holds an x, a y and a z for every point and the section an x and a y for every corner, an empty section gives a circle of sides around the path. Scales and twists are either empty or hold a value for every point of the path, see sweep().
+// Displaced wraps the parameters of any shape with the noise that displaces it.
+// The heightmap of a terrain holds a byte for every pixel with heightmap_width pixels in a row, an empty heightmap gives fractal noise of the seed instead, see terrain().
+// Twist turns the top of a prism against its bottom and the end of a sweep against its start. Truncation cuts that much of the height off the top of a pyramid, so it needs to be smaller than the apex height.
+// The fillet radius of a rounded box is limited to half of its smallest side, see rounded_box().
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShapeParams {
+    Plane { subdivisions: usize },
+    Disk { sides: usize, radius: f32, cap_segments: usize, start_angle: f32, sweep_angle: f32 },
+    Cube { subdivisions: usize },
+    Sphere { subdivisions: usize, mode: SphereMode, smooth: bool },
+    Cylinder {
+        sides: usize,
+        radius: f32,
+        height_segments: usize,
+        cap_segments: usize,
+        start_angle: f32,
+        sweep_angle: f32,
+        side_caps: bool,
+        smooth: bool,
+    },
+    Tube {
+        sides: usize,
+        inner_radius: f32,
+        outer_radius: f32,
+        height_segments: usize,
+        cap_segments: usize,
+        start_angle: f32,
+        sweep_angle: f32,
+        side_caps: bool,
+        smooth: bool,
+    },
+    Cone { sides: usize, bottom_radius: f32, top_radius: f32, height_segments: usize, capped: bool, smooth: bool },
+    Torus { major_radius: f32, minor_radius: f32, radial_segments: usize, tubular_segments: usize, arc: f32, smooth: bool },
+    Capsule { radius: f32, length: f32, radial_segments: usize, cap_rings: usize, smooth: bool },
+    Tetrahedron { subdivisions: usize, spherical: bool },
+    Octahedron { subdivisions: usize, spherical: bool },
+    Dodecahedron { subdivisions: usize, spherical: bool },
+    Icosahedron { subdivisions: usize, spherical: bool },
+    RoundedBox { width: f32, height: f32, depth: f32, fillet_radius: f32, fillet_segments: usize },
+    Ring { sides: usize, inner_radius: f32, outer_radius: f32, cap_segments: usize, start_angle: f32, sweep_angle: f32 },
+    Prism { sides: usize, radius: f32, height: f32, twist: f32, height_segments: usize },
+    Pyramid { sides: usize, radius: f32, height: f32, truncation: f32 },
+    Lathe { profile: Vec<f32>, radial_segments: usize, start_angle: f32, sweep_angle: f32, smooth: bool },
+    Extrude { outline: Vec<f32>, holes: Vec<Vec<f32>>, depth: f32, bevel_size: f32, bevel_segments: usize },
+    Sweep {
+        path: Vec<f32>,
+        section: Vec<f32>,
+        scales: Vec<f32>,
+        twists: Vec<f32>,
+        sides: usize,
+        radius: f32,
+        path_segments: usize,
+        twist: f32,
+        capped: bool,
+        smooth: bool,
+    },
+    Terrain {
+        heightmap: Vec<u8>,
+        heightmap_width: usize,
+        x_subdivisions: usize,
+        y_subdivisions: usize,
+        height: f32,
+        seed: usize,
+        frequency: f32,
+        octaves: usize,
+        lacunarity: f32,
+        gain: f32,
+        smooth: bool,
+    },
+    Displaced { base: Box<ShapeParams>, displacement: Displacement },
+}
+
+impl ShapeParams {
+    // The shape these parameters belong to.
+    pub fn shape(&self) -> Shape {
+        match self {
+            ShapeParams::Plane { .. } => Shape::Plane,
+            ShapeParams::Disk { .. } => Shape::Disk,
+            ShapeParams::Cube { .. } => Shape::Cube,
+            ShapeParams::Sphere { .. } => Shape::Sphere,
+            ShapeParams::Cylinder { .. } => Shape::Cylinder,
+            ShapeParams::Tube { .. } => Shape::Tube,
+            ShapeParams::Cone { .. } => Shape::Cone,
+            ShapeParams::Torus { .. } => Shape::Torus,
+            ShapeParams::Capsule { .. } => Shape::Capsule,
+            ShapeParams::Tetrahedron { .. } => Shape::Tetrahedron,
+            ShapeParams::Octahedron { .. } => Shape::Octahedron,
+            ShapeParams::Dodecahedron { .. } => Shape::Dodecahedron,
+            ShapeParams::Icosahedron { .. } => Shape::Icosahedron,
+            ShapeParams::RoundedBox { .. } => Shape::RoundedBox,
+            ShapeParams::Ring { .. } => Shape::Ring,
+            ShapeParams::Prism { .. } => Shape::Prism,
+            ShapeParams::Pyramid { .. } => Shape::Pyramid,
+            ShapeParams::Lathe { .. } => Shape::Lathe,
+            ShapeParams::Extrude { .. } => Shape::Extrude,
+            ShapeParams::Sweep { .. } => Shape::Sweep,
+            ShapeParams::Terrain { .. } => Shape::Terrain,
+            ShapeParams::Displaced { ref base, .. } => base.shape(),
+        }
+    }
+
+    // The values as numbers, in the order of Shape::params(). The profile of the lathe, the polygons of the extrusion, the path of the sweep and the heightmap of the terrain aren't part of the schema so they are left out.
+    // A displaced shape returns the values of the shape, the displacement has its own values.
+    pub fn values(&self) -> Vec<f32> {
+        let flag = |value: bool| if value { 1.0 } else { 0.0 };
+
+        match *self {
+            ShapeParams::Plane { subdivisions } => vec![subdivisions as f32],
+            ShapeParams::Disk { sides, radius, cap_segments, start_angle, sweep_angle } => {
+                vec![sides as f32, radius, cap_segments as f32, start_angle, sweep_angle]
+            }
+            ShapeParams::Cube { subdivisions } => vec![subdivisions as f32],
+            ShapeParams::Sphere { subdivisions, mode, smooth } => vec![subdivisions as f32, mode as usize as f32, flag(smooth)],
+            ShapeParams::Cylinder { sides, radius, height_segments, cap_segments, start_angle, sweep_angle, side_caps, smooth } => vec![
+                sides as f32,
+                radius,
+                height_segments as f32,
+                cap_segments as f32,
+                start_angle,
+                sweep_angle,
+                flag(side_caps),
+                flag(smooth),
+            ],
+            ShapeParams::Tube { sides, inner_radius, outer_radius, height_segments, cap_segments, start_angle, sweep_angle, side_caps, smooth } => vec![
+                sides as f32,
+                inner_radius,
+                outer_radius,
+                height_segments as f32,
+                cap_segments as f32,
+                start_angle,
+                sweep_angle,
+                flag(side_caps),
+                flag(smooth),
+            ],
+            ShapeParams::Cone { sides, bottom_radius, top_radius, height_segments, capped, smooth } => {
+                vec![sides as f32, bottom_radius, top_radius, height_segments as f32, flag(capped), flag(smooth)]
+            }
+            ShapeParams::Torus { major_radius, minor_radius, radial_segments, tubular_segments, arc, smooth } => {
+                vec![major_radius, minor_radius, radial_segments as f32, tubular_segments as f32, arc, flag(smooth)]
+            }
+            ShapeParams::Capsule { radius, length, radial_segments, cap_rings, smooth } => {
+                vec![radius, length, radial_segments as f32, cap_rings as f32, flag(smooth)]
+            }
+            ShapeParams::Tetrahedron { subdivisions, spherical }
+            | ShapeParams::Octahedron { subdivisions, spherical }
+            | ShapeParams::Dodecahedron { subdivisions, spherical }
+            | ShapeParams::Icosahedron { subdivisions, spherical } => vec![subdivisions as f32, flag(spherical)],
+            ShapeParams::RoundedBox { width, height, depth, fillet_radius, fillet_segments } => {
+                vec![width, height, depth, fillet_radius, fillet_segments as f32]
+            }
+            ShapeParams::Ring { sides, inner_radius, outer_radius, cap_segments, start_angle, sweep_angle } => {
+                vec![sides as f32, inner_radius, outer_radius, cap_segments as f32, start_angle, sweep_angle]
+            }
+            ShapeParams::Prism { sides, radius, height, twist, height_segments } => vec![sides as f32, radius, height, twist, height_segments as f32],
+            ShapeParams::Pyramid { sides, radius, height, truncation } => vec![sides as f32, radius, height, truncation],
+            ShapeParams::Lathe { radial_segments, start_angle, sweep_angle, smooth, .. } => {
+                vec![radial_segments as f32, start_angle, sweep_angle, flag(smooth)]
+            }
+            ShapeParams::Extrude { depth, bevel_size, bevel_segments, .. } => vec![depth, bevel_size, bevel_segments as f32],
+            ShapeParams::Sweep { sides, radius, path_segments, twist, capped, smooth, .. } => {
+                vec![sides as f32, radius, path_segments as f32, twist, flag(capped), flag(smooth)]
+            }
+            ShapeParams::Terrain { x_subdivisions, y_subdivisions, height, seed, frequency, octaves, lacunarity, gain, smooth, .. } => vec![
+                x_subdivisions as f32,
+                y_subdivisions as f32,
+                height,
+                seed as f32,
+                frequency,
+                octaves as f32,
+                lacunarity,
+                gain,
+                flag(smooth),
+            ],
+            ShapeParams::Displaced { ref base, .. } => base.values(),
+        }
+    }
+
+    // Reads the parameters of the shape by name. The value function returns None for a parameter that is not given, which is an error. The lathe gets the default profile, the extrusion the default polygons, the sweep the default path with a round section and the terrain noise instead of a heightmap.
+    pub fn from_values<F>(shape: Shape, value: F) -> Result<ShapeParams, ShapeError>
+    where
+        F: Fn(&'static str) -> Option<f32>,
+    {
+        let values = shape.params().iter()
+            .map(|info| value(info.name).ok_or(ShapeError::MissingArgument(info.name)))
+            .collect::<Result<Vec<f32>, ShapeError>>()?;
+
+        // The values are checked before they are turned into counts, otherwise a negative or infinite count would silently become 0 or usize::MAX.
+        check_values(shape.params(), &values)?;
+
+        let v = &values;
+        let params = match shape {
+            Shape::Plane => ShapeParams::Plane { subdivisions: v[0] as usize },
+            Shape::Disk => ShapeParams::Disk { sides: v[0] as usize, radius: v[1], cap_segments: v[2] as usize, start_angle: v[3], sweep_angle: v[4] },
+            Shape::Cube => ShapeParams::Cube { subdivisions: v[0] as usize },
+            Shape::Sphere => ShapeParams::Sphere { subdivisions: v[0] as usize, mode: SphereMode::ALL[v[1] as usize], smooth: v[2] != 0.0 },
+            Shape::Cylinder => ShapeParams::Cylinder {
+                sides: v[0] as usize,
+                radius: v[1],
+                height_segments: v[2] as usize,
+                cap_segments: v[3] as usize,
+                start_angle: v[4],
+                sweep_angle: v[5],
+                side_caps: v[6] != 0.0,
+                smooth: v[7] != 0.0,
+            },
+            Shape::Tube => ShapeParams::Tube {
+                sides: v[0] as usize,
+                inner_radius: v[1],
+                outer_radius: v[2],
+                height_segments: v[3] as usize,
+                cap_segments: v[4] as usize,
+                start_angle: v[5],
+                sweep_angle: v[6],
+                side_caps: v[7] != 0.0,
+                smooth: v[8] != 0.0,
+            },
+            Shape::Cone => ShapeParams::Cone {
+                sides: v[0] as usize,
+                bottom_radius: v[1],
+                top_radius: v[2],
+                height_segments: v[3] as usize,
+                capped: v[4] != 0.0,
+                smooth: v[5] != 0.0,
+            },
+            Shape::Torus => ShapeParams::Torus {
+                major_radius: v[0],
+                minor_radius: v[1],
+                radial_segments: v[2] as usize,
+                tubular_segments: v[3] as usize,
+                arc: v[4],
+                smooth: v[5] != 0.0,
+            },
+            Shape::Capsule => ShapeParams::Capsule {
+                radius: v[0],
+                length: v[1],
+                radial_segments: v[2] as usize,
+                cap_rings: v[3] as usize,
+                smooth: v[4] != 0.0,
+            },
+            Shape::Tetrahedron => ShapeParams::Tetrahedron { subdivisions: v[0] as usize, spherical: v[1] != 0.0 },
+            Shape::Octahedron => ShapeParams::Octahedron { subdivisions: v[0] as usize, spherical: v[1] != 0.0 },
+            Shape::Dodecahedron => ShapeParams::Dodecahedron { subdivisions: v[0] as usize, spherical: v[1] != 0.0 },
+            Shape::Icosahedron => ShapeParams::Icosahedron { subdivisions: v[0] as usize, spherical: v[1] != 0.0 },
+            Shape::RoundedBox => ShapeParams::RoundedBox {
+                width: v[0],
+                height: v[1],
+                depth: v[2],
+                fillet_radius: v[3],
+                fillet_segments: v[4] as usize,
+            },
+            Shape::Ring => ShapeParams::Ring {
+                sides: v[0] as usize,
+                inner_radius: v[1],
+                outer_radius: v[2],
+                cap_segments: v[3] as usize,
+                start_angle: v[4],
+                sweep_angle: v[5],
+            },
+            Shape::Prism => ShapeParams::Prism { sides: v[0] as usize, radius: v[1], height: v[2], twist: v[3], height_segments: v[4] as usize },
+            Shape::Pyramid => ShapeParams::Pyramid { sides: v[0] as usize, radius: v[1], height: v[2], truncation: v[3] },
+            Shape::Lathe => ShapeParams::Lathe {
+                profile: DEFAULT_PROFILE.to_vec(),
+                radial_segments: v[0] as usize,
+                start_angle: v[1],
+                sweep_angle: v[2],
+                smooth: v[3] != 0.0,
+            },
+            Shape::Extrude => ShapeParams::Extrude {
+                outline: DEFAULT_OUTLINE.to_vec(),
+                holes: vec![DEFAULT_HOLE.to_vec()],
+                depth: v[0],
+                bevel_size: v[1],
+                bevel_segments: v[2] as usize,
+            },
+            Shape::Sweep => ShapeParams::Sweep {
+                path: DEFAULT_PATH.to_vec(),
+                section: Vec::new(),
+                scales: Vec::new(),
+                twists: Vec::new(),
+                sides: v[0] as usize,
+                radius: v[1],
+                path_segments: v[2] as usize,
+                twist: v[3],
+                capped: v[4] != 0.0,
+                smooth: v[5] != 0.0,
+            },
+            Shape::Terrain => ShapeParams::Terrain {
+                heightmap: Vec::new(),
+                heightmap_width: 0,
+                x_subdivisions: v[0] as usize,
+                y_subdivisions: v[1] as usize,
+                height: v[2],
+                seed: v[3] as usize,
+                frequency: v[4],
+                octaves: v[5] as usize,
+                lacunarity: v[6],
+                gain: v[7],
+                smooth: v[8] != 0.0,
+            },
+        };
+
+        params.validate()?;
+        Ok(params)
+    }
+
+    // Reads the parameters from a JS object with a property per parameter name. Booleans are read as 1 or 0 and strings, like the value of an input element, are converted to numbers.
+    // A displacement property wraps the shape in ShapeParams::Displaced, its numbers are read the same way as the parameters.
+    // The profile of the lathe and the outline of the extrusion are read from their property when it is there, which can be an array or a Float32Array. The holes are an array of them. The sweep reads its path, section, scales and twists the same way. The heightmap of the terrain is read as bytes.
+    pub fn from_js(shape_index: usize, object: &JsValue) -> Result<ShapeParams, ShapeError> {
+        let shape = super::usize2Shape(shape_index)?;
+
+        let mut params = ShapeParams::from_values(shape, |name| number(object, name))?;
+
+        let property = |name: &str| {
+            js_sys::Reflect::get(object, &JsValue::from_str(name)).ok().filter(|value| !value.is_undefined() && !value.is_null())
+        };
+        match &mut params {
+            ShapeParams::Lathe { profile, .. } => {
+                if let Some(value) = property("profile") {
+                    *profile = numbers(&value).ok_or(ShapeError::InvalidProfile("must be an array of numbers"))?;
+                }
+            }
+            ShapeParams::Extrude { outline, holes, .. } => {
+                if let Some(value) = property("outline") {
+                    *outline = numbers(&value).ok_or(ShapeError::InvalidPolygon { name: "outline", reason: "must be an array of numbers" })?;
+                }
+                if let Some(value) = property("holes") {
+                    let invalid = ShapeError::InvalidPolygon { name: "holes", reason: "must be an array of arrays of numbers" };
+                    if !js_sys::Array::is_array(&value) {
+                        return Err(invalid);
+                    }
+                    *holes = js_sys::Array::from(&value).iter().map(|hole| numbers(&hole).ok_or(invalid.clone())).collect::<Result<_, _>>()?;
+                }
+            }
+            ShapeParams::Sweep { path, section, scales, twists, .. } => {
+                if let Some(value) = property("path") {
+                    *path = numbers(&value).ok_or(ShapeError::InvalidPath("must be an array of numbers"))?;
+                }
+                if let Some(value) = property("section") {
+                    *section = numbers(&value).ok_or(ShapeError::InvalidPolygon { name: "section", reason: "must be an array of numbers" })?;
+                }
+                if let Some(value) = property("scales") {
+                    *scales = numbers(&value).ok_or(ShapeError::InvalidPath("scales must be an array of numbers"))?;
+                }
+                if let Some(value) = property("twists") {
+                    *twists = numbers(&value).ok_or(ShapeError::InvalidPath("twists must be an array of numbers"))?;
+                }
+            }
+            ShapeParams::Terrain { heightmap, heightmap_width, .. } => {
+                if let Some(value) = property("heightmap") {
+                    if !js_sys::Array::is_array(&value) && !value.is_instance_of::<js_sys::Uint8Array>() && !value.is_instance_of::<js_sys::Uint8ClampedArray>() {
+                        return Err(ShapeError::InvalidHeightmap("must be an array of bytes"));
+                    }
+                    *heightmap = js_sys::Uint8Array::new(&value).to_vec();
+
+                    // Without a width the heightmap has to be square.
+                    *heightmap_width = match property("heightmap_width") {
+                        Some(width) => width.as_f64().filter(|width| width.is_finite() && *width >= 1.0).ok_or(ShapeError::InvalidHeightmap("width must be a number of at least 1"))? as usize,
+                        None => (heightmap.len() as f64).sqrt() as usize,
+                    };
+                }
+            }
+            _ => {}
+        }
+        params.validate()?;
+
+        if let Some(value) = property("displacement") {
+            let displacement = Displacement::from_values(|name| number(&value, name))?;
+            params = ShapeParams::Displaced { base: Box::new(params), displacement };
+        }
+
+        Ok(params)
+    }
+
+    // Checks the values against the schema and the rules between parameters.
+    pub fn validate(&self) -> Result<(), ShapeError> {
+        check_values(self.shape().params(), &self.values())?;
+
+        match *self {
+            ShapeParams::Tube { inner_radius, outer_radius, .. } | ShapeParams::Ring { inner_radius, outer_radius, .. } if inner_radius >= outer_radius => {
+                Err(ShapeError::InnerRadiusTooLarge { inner: inner_radius, outer: outer_radius })
+            }
+            ShapeParams::Pyramid { height, truncation, .. } if truncation > 0.0 && truncation >= height => {
+                Err(ShapeError::TruncationTooLarge { truncation, height })
+            }
+            ShapeParams::Lathe { ref profile, .. } => check_profile(profile),
+            ShapeParams::Extrude { ref outline, ref holes, depth, bevel_size, .. } => {
+                check_polygon("outline", outline)?;
+                holes.iter().try_for_each(|hole| check_polygon("holes", hole))?;
+                if super::bevel_fits(outline, holes, depth, bevel_size) {
+                    Ok(())
+                } else {
+                    Err(ShapeError::InvalidPolygon { name: "outline", reason: "is too thin for the bevel" })
+                }
+            }
+            ShapeParams::Sweep { ref path, ref section, ref scales, ref twists, .. } => {
+                check_path(path, scales, twists)?;
+                if section.is_empty() { Ok(()) } else { check_polygon("section", section) }
+            }
+            ShapeParams::Displaced { ref base, ref displacement } => {
+                check_values(Displacement::params(), &displacement.values())?;
+                base.validate()
+            }
+            ShapeParams::Terrain { ref heightmap, heightmap_width, .. } if !heightmap.is_empty() => {
+                if heightmap_width == 0 {
+                    Err(ShapeError::InvalidHeightmap("needs a width of at least 1"))
+                } else if heightmap.len() % heightmap_width != 0 {
+                    Err(ShapeError::InvalidHeightmap("needs the same number of pixels in every row"))
+                } else {
+                    Ok(())
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // Returns an upper bound of the number of vertices the shape gets, so a shape that is too large can be refused before it is made. Counts can multiply each other, so this is an f64 that can't overflow.
+    // Hard edges give every triangle its own vertices, which is about 6 per vertex of a smooth grid.
+    pub fn max_vertices(&self) -> f64 {
+        let count = |value: usize| value as f64;
+        let grid = |columns: usize, rows: usize| (count(columns) + 1.0) * (count(rows) + 1.0);
+        let hard_edges = |vertices: f64, smooth: bool| if smooth { vertices } else { vertices * 6.0 };
+
+        match *self {
+            ShapeParams::Plane { subdivisions } => grid(subdivisions, subdivisions),
+            ShapeParams::Terrain { x_subdivisions, y_subdivisions, smooth, .. } => hard_edges(grid(x_subdivisions, y_subdivisions), smooth),
+            ShapeParams::Disk { sides, cap_segments, .. } | ShapeParams::Ring { sides, cap_segments, .. } => grid(sides + 1, cap_segments),
+            ShapeParams::Cube { subdivisions } => 6.0 * grid(subdivisions, subdivisions),
+            ShapeParams::Sphere { subdivisions, mode, smooth } => {
+                let vertices = match mode {
+                    SphereMode::Cube => 6.0 * grid(subdivisions, subdivisions),
+                    SphereMode::Uv => grid(subdivisions * 2, subdivisions * 4),
+                    SphereMode::Ico => 10.0 * grid(subdivisions, subdivisions + 1),
+                };
+                hard_edges(vertices, smooth)
+            }
+            ShapeParams::Cylinder { sides, height_segments, cap_segments, smooth, .. } => {
+                hard_edges(grid(sides, height_segments) + 2.0 * grid(sides + 1, cap_segments) + 2.0 * grid(cap_segments, height_segments), smooth)
+            }
+            ShapeParams::Tube { sides, height_segments, cap_segments, smooth, .. } => {
+                hard_edges(2.0 * grid(sides, height_segments) + 2.0 * grid(sides, cap_segments) + 2.0 * grid(cap_segments, height_segments), smooth)
+            }
+            ShapeParams::Cone { sides, height_segments, smooth, .. } => hard_edges(grid(sides, height_segments) + 2.0 * grid(sides + 1, 1), smooth),
+            ShapeParams::Torus { radial_segments, tubular_segments, smooth, .. } => {
+                hard_edges(grid(radial_segments, tubular_segments) + 2.0 * grid(tubular_segments + 1, 1), smooth)
+            }
+            ShapeParams::Capsule { radial_segments, cap_rings, smooth, .. } => hard_edges(grid(radial_segments, 2 * cap_rings + 3), smooth),
+            // Every triangle of a solid is split into rows of subdivisions + 1 vertices at most, the dodecahedron has 60 triangles as its pentagons are split into 5.
+            ShapeParams::Tetrahedron { subdivisions, .. } => 2.0 * grid(subdivisions, subdivisions + 1),
+            ShapeParams::Octahedron { subdivisions, .. } => 4.0 * grid(subdivisions, subdivisions + 1),
+            ShapeParams::Dodecahedron { subdivisions, .. } => 30.0 * grid(subdivisions, subdivisions + 1),
+            ShapeParams::Icosahedron { subdivisions, .. } => 10.0 * grid(subdivisions, subdivisions + 1),
+            ShapeParams::RoundedBox { fillet_segments, .. } => 6.0 * grid(2 * fillet_segments + 2, 2 * fillet_segments + 2),
+            ShapeParams::Prism { sides, height_segments, .. } => hard_edges(grid(sides, height_segments) + 2.0 * grid(sides + 1, 1), false),
+            ShapeParams::Pyramid { sides, .. } => hard_edges(grid(sides, 1) + 2.0 * grid(sides + 1, 1), false),
+            ShapeParams::Lathe { ref profile, radial_segments, smooth, .. } => {
+                hard_edges(grid(radial_segments, profile.len() / 2) + 2.0 * grid(profile.len() / 2, 1), smooth)
+            }
+            ShapeParams::Extrude { ref outline, ref holes, bevel_segments, .. } => {
+                let corners = count(outline.len() / 2 + holes.iter().map(|hole| hole.len() / 2).sum::<usize>());
+                2.0 * corners * (2.0 * count(bevel_segments) + 2.0) + 2.0 * corners
+            }
+            ShapeParams::Sweep { ref path, ref section, sides, path_segments, smooth, .. } => {
+                let corners = if section.is_empty() { sides } else { section.len() / 2 };
+                let samples = count(path.len() / 3) * count(path_segments) + 1.0;
+                hard_edges(samples * (count(corners) + 1.0) + 2.0 * count(corners), smooth)
+            }
+            ShapeParams::Displaced { ref base, .. } => base.max_vertices(),
+        }
+    }
+}
+
+// Checks that the profile of a lathe holds at least two points with a finite radius of at least 0 and a finite height.
+fn check_profile(profile: &[f32]) -> Result<(), ShapeError> {
+    if profile.len() % 2 != 0 {
+        Err(ShapeError::InvalidProfile("needs a radius and a height for every point"))
+    } else if profile.len() < 4 {
+        Err(ShapeError::InvalidProfile("needs at least 2 points"))
+    } else if !profile.iter().all(|value| value.is_finite()) {
+        Err(ShapeError::InvalidProfile("can only hold finite numbers"))
+    } else if profile.chunks(2).any(|point| point[0] < 0.0) {
+        Err(ShapeError::InvalidProfile("can't have a radius below 0"))
+    } else {
+        Ok(())
+    }
+}
+
+// Checks that a polygon of an extrusion or the section of a sweep holds at least three corners with a finite x and y.
+fn check_polygon(name: &'static str, polygon: &[f32]) -> Result<(), ShapeError> {
+    if polygon.len() % 2 != 0 {
+        Err(ShapeError::InvalidPolygon { name, reason: "need an x and a y for every corner" })
+    } else if polygon.len() < 6 {
+        Err(ShapeError::InvalidPolygon { name, reason: "need at least 3 corners" })
+    } else if !polygon.iter().all(|value| value.is_finite()) {
+        Err(ShapeError::InvalidPolygon { name, reason: "can only hold finite numbers" })
+    } else {
+        Ok(())
+    }
+}
+
+// Checks that the path of a sweep holds at least two finite points, and that the scales and twists are either empty or hold a finite value for every point.
+fn check_path(path: &[f32], scales: &[f32], twists: &[f32]) -> Result<(), ShapeError> {
+    let points = path.len() / 3;
+    if path.len() % 3 != 0 {
+        Err(ShapeError::InvalidPath("needs an x, a y and a z for every point"))
+    } else if points < 2 {
+        Err(ShapeError::InvalidPath("needs at least 2 points"))
+    } else if !path.iter().all(|value| value.is_finite()) {
+        Err(ShapeError::InvalidPath("can only hold finite numbers"))
+    } else if path.chunks(3).all(|point| point.iter().zip(path).map(|(a, b)| (a - b) * (a - b)).sum::<f32>() <= 1e-12) {
+        // The sweep leaves out points that repeat, a path of one point has no direction to follow.
+        Err(ShapeError::InvalidPath("needs at least 2 distinct points"))
+    } else if !scales.is_empty() && scales.len() != points {
+        Err(ShapeError::InvalidPath("needs a scale for every point"))
+    } else if !twists.is_empty() && twists.len() != points {
+        Err(ShapeError::InvalidPath("needs a twist for every point"))
+    } else if !scales.iter().chain(twists).all(|value| value.is_finite()) {
+        Err(ShapeError::InvalidPath("can only have finite scales and twists"))
+    } else if scales.iter().any(|&scale| scale < 0.0) {
+        Err(ShapeError::InvalidPath("can't have a scale below 0"))
+    } else {
+        Ok(())
+    }
+}
+
+// Reads a property of a JS object as a number, or None when it is not there. Booleans are read as 1 or 0.
+fn number(object: &JsValue, name: &str) -> Option<f32> {
+    let value = js_sys::Reflect::get(object, &JsValue::from_str(name)).ok()?;
+    if value.is_undefined() || value.is_null() {
+        None
+    } else if let Some(flag) = value.as_bool() {
+        Some(if flag { 1.0 } else { 0.0 })
+    } else {
+        Some(value.unchecked_into_f64() as f32)
+    }
+}
+
+// Returns the numbers in a JS array or Float32Array, or None when the value is neither.
+fn numbers(value: &JsValue) -> Option<Vec<f32>> {
+    if js_sys::Array::is_array(value) || value.is_instance_of::<js_sys::Float32Array>() {
+        Some(js_sys::Float32Array::new(value).to_vec())
+    } else {
+        None
+    }
+}
+
+// Checks every value against the type and the range in the schema.
+fn check_values(params: &[ParamInfo], values: &[f32]) -> Result<(), ShapeError> {
+    for (info, &value) in params.iter().zip(values) {
+        match info.kind {
+            ParamKind::Count if !(info.min..=info.max).contains(&value) => {
+                return Err(ShapeError::InvalidCount { name: info.name, value, min: info.min, max: info.max })
+            }
+            ParamKind::Radius if !value.is_finite() || value < info.min => return Err(ShapeError::InvalidRadius(info.name, value)),
+            ParamKind::Length if !value.is_finite() || value < info.min => return Err(ShapeError::InvalidLength(info.name, value)),
+            ParamKind::Angle if !(info.min..=info.max).contains(&value) => {
+                return Err(ShapeError::InvalidAngle { name: info.name, value, min: info.min, max: info.max })
+            }
+            ParamKind::Choice(options) if value.fract() != 0.0 || !(0.0..options.len() as f32).contains(&value) => {
+                return Err(ShapeError::InvalidChoice(info.name, value))
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,101 @@
+/* ==== PLY ====
+
+    PLY files consist of a text header that describes the elements in the file, followed by the elements themselves.
+    There are two flavours of PLY:
+    - ASCII                 - The elements are written as text, one vertex or face per line.
+    - Binary little endian  - The elements are written as raw little endian numbers.
+
+    Every vertex has a position and optionally a normal and an RGB color. Every face is a list of three vertex indices.
+    The colors are passed as 3 u8's per vertex, so a color per triangle needs a mesh where the triangles don't share their vertices, see ShapeData::split_triangles.
+
+  =================== */
+
+use super::ShapeData;
+
+// Returns the shape data as an ASCII PLY file. Colors holds 3 u8's per vertex.
+pub fn ply_ascii(shape_data: &ShapeData, normals: bool, colors: Option<&[u8]>) -> String {
+    let mut data = header(shape_data, "ascii", normals, colors.is_some());
+
+    for i in 0..shape_data.nr_of_vertices() {
+        let position = &shape_data.vertices[i * 3..i * 3 + 3];
+        data.push_str(&format!("{} {} {}", position[0], position[1], position[2]));
+
+        if normals {
+            let normal = &shape_data.normals[i * 3..i * 3 + 3];
+            data.push_str(&format!(" {} {} {}", normal[0], normal[1], normal[2]));
+        }
+
+        if let Some(colors) = colors {
+            let color = &colors[i * 3..i * 3 + 3];
+            data.push_str(&format!(" {} {} {}", color[0], color[1], color[2]));
+        }
+
+        data.push('\n');
+    }
+
+    for triangle in shape_data.indices.chunks(3) {
+        data.push_str(&format!("3 {} {} {}\n", triangle[0], triangle[1], triangle[2]));
+    }
+
+    data
+}
+
+// Returns the shape data as a binary little endian PLY file. Colors holds 3 u8's per vertex.
+pub fn ply_binary(shape_data: &ShapeData, normals: bool, colors: Option<&[u8]>) -> Vec<u8> {
+    let mut data = header(shape_data, "binary_little_endian", normals, colors.is_some()).into_bytes();
+
+    for i in 0..shape_data.nr_of_vertices() {
+        for value in shape_data.vertices[i * 3..i * 3 + 3].iter() {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+
+        if normals {
+            for value in shape_data.normals[i * 3..i * 3 + 3].iter() {
+                data.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+
+        if let Some(colors) = colors {
+            data.extend_from_slice(&colors[i * 3..i * 3 + 3]);
+        }
+    }
+
+    for triangle in shape_data.indices.chunks(3) {
+        data.push(3);
+        for index in triangle.iter() {
+            data.extend_from_slice(&index.to_le_bytes());
+        }
+    }
+
+    data
+}
+
+// Returns the PLY header which describes the properties of the vertices and faces that follow it.
+fn header(shape_data: &ShapeData, format: &str, normals: bool, colors: bool) -> String {
+    let mut header = String::new();
+
+    header.push_str("ply\n");
+    header.push_str(&format!("format {} 1.0\n", format));
+    header.push_str("comment Simple Primitives\n");
+
+    header.push_str(&format!("element vertex {}\n", shape_data.nr_of_vertices()));
+    header.push_str("property float x\n");
+    header.push_str("property float y\n");
+    header.push_str("property float z\n");
+    if normals {
+        header.push_str("property float nx\n");
+        header.push_str("property float ny\n");
+        header.push_str("property float nz\n");
+    }
+    if colors {
+        header.push_str("property uchar red\n");
+        header.push_str("property uchar green\n");
+        header.push_str("property uchar blue\n");
+    }
+
+    header.push_str(&format!("element face {}\n", shape_data.nr_of_triangles()));
+    header.push_str("property list uchar uint vertex_indices\n");
+    header.push_str("end_header\n");
+
+    header
+}
@@ -0,0 +1,69 @@
+/* ==== STL ====
+
+    STL only knows loose triangles, so every triangle is written with its own three corners and a facet normal.
+    The facet normal is calculated from the winding of the triangle, so it points to the side where the corners are in counter clockwise order.
+
+    There are two flavours of STL:
+    - Binary        - An 80 byte header, the amount of triangles and then 50 bytes per triangle. All numbers are little endian.
+    - ASCII         - A human readable text file with a facet block per triangle.
+
+  =================== */
+
+use super::{face_normal, ShapeData};
+
+// Returns the shape data as a binary STL file.
+pub fn stl_binary(shape_data: &ShapeData) -> Vec<u8> {
+    let nr_of_triangles = shape_data.nr_of_triangles();
+    let mut data: Vec<u8> = Vec::with_capacity(84 + nr_of_triangles * 50);
+
+    // The header may contain anything as long as it doesn't start with "solid", which would make readers think it is an ASCII file.
+    let mut header = [b' '; 80];
+    let title = b"Simple Primitives";
+    header[..title.len()].copy_from_slice(title);
+    data.extend_from_slice(&header);
+
+    data.extend_from_slice(&(nr_of_triangles as u32).to_le_bytes());
+
+    for triangle in shape_data.indices.chunks(3) {
+        let a = shape_data.position(triangle[0]);
+        let b = shape_data.position(triangle[1]);
+        let c = shape_data.position(triangle[2]);
+
+        for vector in [face_normal(a, b, c), a, b, c].iter() {
+            for component in vector.iter() {
+                data.extend_from_slice(&component.to_le_bytes());
+            }
+        }
+
+        // The attribute byte count, which is unused by almost every program.
+        data.extend_from_slice(&0u16.to_le_bytes());
+    }
+
+    data
+}
+
+// Returns the shape data as an ASCII STL file. The name is written after "solid" and "endsolid" and shouldn't contain any whitespace.
+pub fn stl_ascii(shape_data: &ShapeData, name: &str) -> String {
+    let mut data = String::new();
+
+    data.push_str(&format!("solid {}\n", name));
+
+    for triangle in shape_data.indices.chunks(3) {
+        let a = shape_data.position(triangle[0]);
+        let b = shape_data.position(triangle[1]);
+        let c = shape_data.position(triangle[2]);
+        let normal = face_normal(a, b, c);
+
+        data.push_str(&format!("  facet normal {:e} {:e} {:e}\n", normal[0], normal[1], normal[2]));
+        data.push_str("    outer loop\n");
+        for vertex in [a, b, c].iter() {
+            data.push_str(&format!("      vertex {:e} {:e} {:e}\n", vertex[0], vertex[1], vertex[2]));
+        }
+        data.push_str("    endloop\n");
+        data.push_str("  endfacet\n");
+    }
+
+    data.push_str(&format!("endsolid {}\n", name));
+
+    data
+}
@@ -0,0 +1,204 @@
+/* ==== Triangulate ====
+
+    Splits a simple polygon, one that doesn't cross itself, into triangles with ear clipping.
+    An ear is a corner of the polygon where the triangle with its two neighbours lies inside the polygon and holds no other corners. Cutting off an ear leaves a smaller polygon, so we keep cutting off ears until one triangle is left.
+    A polygon with holes is first turned into a single polygon by bridge_holes(), which cuts a bridge from every hole to the outline so the hole becomes part of its border.
+
+  =================== */
+
+// Returns twice the signed area of the polygon. It is positive when the corners are in counter clockwise order.
+pub fn signed_area(points: &[[f32; 2]]) -> f32 {
+    let mut area = 0.0;
+
+    for i in 0..points.len() {
+        let a = points[i];
+        let b = points[(i + 1) % points.len()];
+        area += a[0] * b[1] - b[0] * a[1];
+    }
+
+    area
+}
+
+// Returns twice the signed area of the triangle a, b, c. It is positive when the corners are in counter clockwise order.
+fn cross(a: [f32; 2], b: [f32; 2], c: [f32; 2]) -> f32 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+// Returns true when the point lies inside or on the edge of the counter clockwise triangle a, b, c.
+fn in_triangle(point: [f32; 2], a: [f32; 2], b: [f32; 2], c: [f32; 2]) -> bool {
+    cross(a, b, point) >= 0.0 && cross(b, c, point) >= 0.0 && cross(c, a, point) >= 0.0
+}
+
+// Returns the triangles of the polygon as indices into points. The triangles have the same winding as the polygon.
+pub fn triangulate(points: &[[f32; 2]]) -> Vec<[usize; 3]> {
+    let mut triangles: Vec<[usize; 3]> = Vec::new();
+    if points.len() < 3 {
+        return triangles;
+    }
+
+    // Ear clipping works on a counter clockwise polygon, so a clockwise polygon is walked backwards and its triangles are flipped at the end.
+    let clockwise = signed_area(points) < 0.0;
+    let mut remaining: Vec<usize> = (0..points.len()).collect();
+    if clockwise {
+        remaining.reverse();
+    }
+
+    while remaining.len() > 3 {
+        let count = remaining.len();
+        let mut ear = None;
+
+        for i in 0..count {
+            let previous = remaining[(i + count - 1) % count];
+            let current = remaining[i];
+            let next = remaining[(i + 1) % count];
+            let (a, b, c) = (points[previous], points[current], points[next]);
+
+            // A corner that turns clockwise is a reflex corner and can't be an ear.
+            if cross(a, b, c) <= 0.0 {
+                continue;
+            }
+
+            let holds_other_corner = remaining.iter().any(|&other| {
+                other != previous && other != current && other != next
+                    && points[other] != a && points[other] != b && points[other] != c
+                    && in_triangle(points[other], a, b, c)
+            });
+
+            if !holds_other_corner {
+                ear = Some(i);
+                break;
+            }
+        }
+
+        // A polygon that crosses itself or has only collinear corners left has no ears, so we cut off any corner to make sure we finish.
+        let i = ear.unwrap_or(0);
+        triangles.push([
+            remaining[(i + count - 1) % count],
+            remaining[i],
+            remaining[(i + 1) % count],
+        ]);
+        remaining.remove(i);
+    }
+
+    triangles.push([remaining[0], remaining[1], remaining[2]]);
+
+    if clockwise {
+        for triangle in triangles.iter_mut() {
+            triangle.swap(1, 2);
+        }
+    }
+
+    triangles
+}
+
+// Returns the polygon that walks along the outline and every hole as indices into the corners of the outline followed by the corners of every hole.
+// The outline has to be counter clockwise and the holes clockwise. The hole that reaches furthest to the right goes first: a ray from its rightmost corner to the right hits an edge of the polygon,
+// and the corner of that edge that lies furthest to the right can be seen from the hole, unless another corner lies in the triangle between them. Then the one closest to the ray can be seen.
+// The polygon walks from that corner into the hole, around it and back again, so both sides of the bridge get the same corners.
+pub fn bridge_holes(outline: &[[f32; 2]], holes: &[Vec<[f32; 2]>]) -> Vec<usize> {
+    let points: Vec<[f32; 2]> = outline.iter().chain(holes.iter().flatten()).copied().collect();
+    let mut polygon: Vec<usize> = (0..outline.len()).collect();
+
+    // The corners of every hole and the rightmost one among them.
+    let mut start = outline.len();
+    let mut ordered: Vec<(Vec<usize>, usize)> = holes
+        .iter()
+        .filter(|hole| !hole.is_empty())
+        .map(|hole| {
+            let corners: Vec<usize> = (start..start + hole.len()).collect();
+            start += hole.len();
+            let right = (0..corners.len()).fold(0, |best, k| if points[corners[k]][0] > points[corners[best]][0] { k } else { best });
+            (corners, right)
+        })
+        .collect();
+    ordered.sort_by(|a, b| points[b.0[b.1]][0].total_cmp(&points[a.0[a.1]][0]));
+
+    for (corners, right) in ordered {
+        let m = points[corners[right]];
+
+        // The closest edge the ray hits, as the position of the hit and the position in the polygon of the corner that lies furthest to the right.
+        let mut hit: Option<(f32, usize)> = None;
+        for k in 0..polygon.len() {
+            let (a, b) = (points[polygon[k]], points[polygon[(k + 1) % polygon.len()]]);
+            if (a[1] > m[1]) == (b[1] > m[1]) {
+                continue;
+            }
+            let x = a[0] + (m[1] - a[1]) / (b[1] - a[1]) * (b[0] - a[0]);
+            if x >= m[0] && hit.map_or(true, |(closest, _)| x < closest) {
+                hit = Some((x, if a[0] > b[0] { k } else { (k + 1) % polygon.len() }));
+            }
+        }
+
+        // An outline that doesn't surround the hole has no edge to the right of it, the closest corner is all we can do then.
+        let distance = |k: usize| {
+            let point = points[polygon[k]];
+            (point[0] - m[0]).powi(2) + (point[1] - m[1]).powi(2)
+        };
+        let visible = match hit {
+            Some((x, candidate)) => {
+                let (i, p) = ([x, m[1]], points[polygon[candidate]]);
+                let (b, c) = if cross(m, i, p) < 0.0 { (p, i) } else { (i, p) };
+                let angle = |k: usize| {
+                    let point = points[polygon[k]];
+                    (point[1] - m[1]).abs().atan2(point[0] - m[0])
+                };
+                (0..polygon.len())
+                    .filter(|&k| k != candidate && points[polygon[k]] != p && points[polygon[k]] != m && in_triangle(points[polygon[k]], m, b, c))
+                    .fold(candidate, |best, k| {
+                        if (angle(k), distance(k)) < (angle(best), distance(best)) { k } else { best }
+                    })
+            }
+            None => (0..polygon.len()).fold(0, |best, k| if distance(k) < distance(best) { k } else { best }),
+        };
+
+        let bridge = corners[right..].iter().chain(&corners[..=right]).copied().chain(std::iter::once(polygon[visible]));
+        polygon.splice(visible + 1..visible + 1, bridge.collect::<Vec<usize>>());
+    }
+
+    polygon
+}
+
+// Returns true when an edge of one of the closed contours touches or crosses another edge that isn't its neighbour.
+pub fn contours_cross(contours: &[Vec<[f32; 2]>]) -> bool {
+    let edges: Vec<(usize, usize, [f32; 2], [f32; 2])> = contours
+        .iter()
+        .enumerate()
+        .flat_map(|(c, corners)| (0..corners.len()).map(move |k| (c, k, corners[k], corners[(k + 1) % corners.len()])))
+        .collect();
+
+    edges.iter().enumerate().any(|(i, &(c1, k1, a, b))| {
+        edges[i + 1..].iter().any(|&(c2, k2, c, d)| {
+            let count = contours[c1].len();
+            let neighbours = c1 == c2 && ((k1 + 1) % count == k2 || (k2 + 1) % count == k1);
+            !neighbours && edges_meet(a, b, c, d)
+        })
+    })
+}
+
+// Returns true when the edge a, b touches or crosses the edge c, d.
+fn edges_meet(a: [f32; 2], b: [f32; 2], c: [f32; 2], d: [f32; 2]) -> bool {
+    let (d1, d2, d3, d4) = (cross(a, b, c), cross(a, b, d), cross(c, d, a), cross(c, d, b));
+    if d1 * d2 < 0.0 && d3 * d4 < 0.0 {
+        return true;
+    }
+
+    // A corner that lies on the other edge touches it.
+    let on_edge = |p: [f32; 2], q: [f32; 2], point: [f32; 2]| {
+        (p[0].min(q[0])..=p[0].max(q[0])).contains(&point[0]) && (p[1].min(q[1])..=p[1].max(q[1])).contains(&point[1])
+    };
+    (d1 == 0.0 && on_edge(a, b, c)) || (d2 == 0.0 && on_edge(a, b, d)) || (d3 == 0.0 && on_edge(c, d, a)) || (d4 == 0.0 && on_edge(c, d, b))
+}
+
+// Returns true when the point lies inside the polygon, by counting how many edges a ray from the point to the right crosses.
+pub fn contains(polygon: &[[f32; 2]], point: [f32; 2]) -> bool {
+    let mut inside = false;
+
+    for i in 0..polygon.len() {
+        let (a, b) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+        if (a[1] > point[1]) != (b[1] > point[1]) && point[0] < a[0] + (point[1] - a[1]) * (b[0] - a[0]) / (b[1] - a[1]) {
+            inside = !inside;
+        }
+    }
+
+    inside
+}
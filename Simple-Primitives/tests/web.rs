@@ -17,10 +17,915 @@ fn pass() {
 
 #[wasm_bindgen_test]
 fn test() {
-    generate_geometry(0, vec![1.5, 1.5, 1.5], vec![0.10]);
-    generate_geometry(1, vec![1.5, 1.5, 1.5], vec![0.1, 0.10]);
-    generate_geometry(2, vec![1.5, 1.5, 1.5], vec![0.10]);
-    generate_geometry(3, vec![1.5, 1.5, 1.5], vec![0.10]);
-    generate_geometry(4, vec![1.5, 1.5, 1.5], vec![0.1, 0.10]);
-    generate_geometry(5, vec![1.5, 1.5, 1.5], vec![0.10, 0.8, 1.0]);
-}
\ No newline at end of file
+    let scale = || vec![1.5, 1.5, 1.5];
+    assert!(generate_geometry(&params(Shape::Plane, &[10.0]).unwrap(), scale()).is_ok());
+    assert!(generate_geometry(&params(Shape::Disk, &[10.0, 1.0, 1.0, 0.0, 360.0]).unwrap(), scale()).is_ok());
+    assert!(generate_geometry(&params(Shape::Cube, &[10.0]).unwrap(), scale()).is_ok());
+    assert!(generate_geometry(&params(Shape::Sphere, &[10.0, 0.0, 1.0]).unwrap(), scale()).is_ok());
+    assert!(generate_geometry(&params(Shape::Cylinder, &[10.0, 1.0, 1.0, 1.0, 0.0, 360.0, 1.0, 1.0]).unwrap(), scale()).is_ok());
+    assert!(generate_geometry(&params(Shape::Tube, &[10.0, 0.8, 1.0, 1.0, 1.0, 0.0, 360.0, 1.0, 1.0]).unwrap(), scale()).is_ok());
+}
+
+// Reads the parameters of the shape from values in the order of the schema, just like JS passes them by name.
+fn params(shape: Shape, values: &[f32]) -> Result<ShapeParams, ShapeError> {
+    ShapeParams::from_values(shape, |name| {
+        shape.params().iter().position(|info| info.name == name).and_then(|index| values.get(index).copied())
+    })
+}
+
+// Every triangle of a closed convex shape around the origin should face away from the origin.
+fn assert_faces_outwards(shape_data: &ShapeData) {
+    for triangle in shape_data.indices.chunks(3) {
+        let p = |i: u32| {
+            let i = i as usize * 3;
+            [shape_data.vertices[i], shape_data.vertices[i + 1], shape_data.vertices[i + 2]]
+        };
+        let (a, b, c) = (p(triangle[0]), p(triangle[1]), p(triangle[2]));
+        let ab = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+        let ac = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+        let normal = [
+            ab[1] * ac[2] - ab[2] * ac[1],
+            ab[2] * ac[0] - ab[0] * ac[2],
+            ab[0] * ac[1] - ab[1] * ac[0],
+        ];
+        let center = [
+            (a[0] + b[0] + c[0]) / 3.0,
+            (a[1] + b[1] + c[1]) / 3.0,
+            (a[2] + b[2] + c[2]) / 3.0,
+        ];
+        assert!(normal[0] * center[0] + normal[1] * center[1] + normal[2] * center[2] > 0.0);
+    }
+}
+
+#[wasm_bindgen_test]
+fn indexed_mesh() {
+    let cube = generate_geometry(&ShapeParams::Cube { subdivisions: 2 }, vec![1.0, 1.0, 1.0]).unwrap();
+    assert_eq!(cube.nr_of_vertices(), 6 * 9);
+    assert_eq!(cube.nr_of_triangles(), 6 * 8);
+    assert!(cube.indices.iter().all(|&i| (i as usize) < cube.nr_of_vertices()));
+
+    let disk = generate_geometry(&ShapeParams::Disk { sides: 10, radius: 1.0, cap_segments: 1, start_angle: 0.0, sweep_angle: 360.0 }, vec![1.0, 1.0, 1.0]).unwrap();
+    assert_eq!(disk.nr_of_vertices(), 11);
+    assert_eq!(disk.nr_of_triangles(), 10);
+}
+
+#[wasm_bindgen_test]
+fn winding() {
+    assert_faces_outwards(&generate_geometry(&ShapeParams::Cube { subdivisions: 3 }, vec![1.0, 1.0, 1.0]).unwrap());
+    assert_faces_outwards(&generate_geometry(&ShapeParams::Sphere { subdivisions: 3, mode: SphereMode::Cube, smooth: true }, vec![1.0, 1.0, 1.0]).unwrap());
+    assert_faces_outwards(&generate_geometry(&ShapeParams::Cylinder { sides: 12, radius: 1.0, height_segments: 1, cap_segments: 1, start_angle: 0.0, sweep_angle: 360.0, side_caps: true, smooth: true }, vec![1.0, 1.0, 1.0]).unwrap());
+}
+
+// Every vertex normal should have a length of 1 and point to the same side as the triangles that use it.
+fn assert_normals_match_winding(shape_data: &ShapeData) {
+    assert_eq!(shape_data.normals.len(), shape_data.vertices.len());
+    for normal in shape_data.normals.chunks(3) {
+        let length = (normal[0] * normal[0] + normal[1] * normal[1] + normal[2] * normal[2]).sqrt();
+        assert!((length - 1.0).abs() < 1e-4);
+    }
+    for triangle in shape_data.indices.chunks(3) {
+        let p = |i: u32| {
+            let i = i as usize * 3;
+            [shape_data.vertices[i], shape_data.vertices[i + 1], shape_data.vertices[i + 2]]
+        };
+        let (a, b, c) = (p(triangle[0]), p(triangle[1]), p(triangle[2]));
+        let ab = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+        let ac = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+        let face = [
+            ab[1] * ac[2] - ab[2] * ac[1],
+            ab[2] * ac[0] - ab[0] * ac[2],
+            ab[0] * ac[1] - ab[1] * ac[0],
+        ];
+        for &index in triangle {
+            let n = &shape_data.normals[index as usize * 3..index as usize * 3 + 3];
+            assert!(face[0] * n[0] + face[1] * n[1] + face[2] * n[2] > 0.0);
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+fn normals() {
+    assert_normals_match_winding(&generate_geometry(&ShapeParams::Plane { subdivisions: 3 }, vec![1.0, 1.0, 1.0]).unwrap());
+    assert_normals_match_winding(&generate_geometry(&ShapeParams::Disk { sides: 10, radius: 1.0, cap_segments: 1, start_angle: 0.0, sweep_angle: 360.0 }, vec![1.0, 1.0, 1.0]).unwrap());
+    assert_normals_match_winding(&generate_geometry(&ShapeParams::Cube { subdivisions: 3 }, vec![1.0, 2.0, 3.0]).unwrap());
+    assert_normals_match_winding(&generate_geometry(&ShapeParams::Sphere { subdivisions: 3, mode: SphereMode::Cube, smooth: true }, vec![1.0, 1.0, 1.0]).unwrap());
+    assert_normals_match_winding(&generate_geometry(&ShapeParams::Cylinder { sides: 12, radius: 1.0, height_segments: 1, cap_segments: 1, start_angle: 0.0, sweep_angle: 360.0, side_caps: true, smooth: true }, vec![2.0, 1.0, 0.5]).unwrap());
+    assert_normals_match_winding(&generate_geometry(&ShapeParams::Tube { sides: 12, inner_radius: 0.8, outer_radius: 1.0, height_segments: 1, cap_segments: 1, start_angle: 0.0, sweep_angle: 360.0, side_caps: true, smooth: true }, vec![1.0, 1.0, 1.0]).unwrap());
+    assert_normals_match_winding(&generate_geometry(&ShapeParams::Tube { sides: 12, inner_radius: 0.8, outer_radius: 1.0, height_segments: 1, cap_segments: 1, start_angle: 0.0, sweep_angle: 360.0, side_caps: true, smooth: false }, vec![1.0, 1.0, 1.0]).unwrap());
+
+    // A negative scale mirrors the shape, the triangles still face outwards.
+    let mirrored = generate_geometry(&ShapeParams::Cube { subdivisions: 3 }, vec![-1.0, 2.0, 3.0]).unwrap();
+    assert_faces_outwards(&mirrored);
+    assert_normals_match_winding(&mirrored);
+    let mirrored = generate_geometry(&ShapeParams::Sphere { subdivisions: 3, mode: SphereMode::Uv, smooth: false }, vec![-1.0, -1.0, -1.0]).unwrap();
+    assert_faces_outwards(&mirrored);
+    assert_normals_match_winding(&mirrored);
+    let mirrored = generate_geometry(&Shape::RoundedBox.default_params(), vec![1.0, -2.0, 1.0]).unwrap();
+    assert_faces_outwards(&mirrored);
+    assert_normals_match_winding(&mirrored);
+}
+
+#[wasm_bindgen_test]
+fn hard_edges() {
+    let smooth = generate_geometry(&ShapeParams::Cylinder { sides: 12, radius: 1.0, height_segments: 1, cap_segments: 1, start_angle: 0.0, sweep_angle: 360.0, side_caps: true, smooth: true }, vec![1.0, 1.0, 1.0]).unwrap();
+    let hard = generate_geometry(&ShapeParams::Cylinder { sides: 12, radius: 1.0, height_segments: 1, cap_segments: 1, start_angle: 0.0, sweep_angle: 360.0, side_caps: true, smooth: false }, vec![1.0, 1.0, 1.0]).unwrap();
+    assert_eq!(hard.nr_of_triangles(), smooth.nr_of_triangles());
+    assert_eq!(hard.nr_of_vertices(), hard.nr_of_triangles() * 3);
+}
+
+// UV coordinates should stay inside the texture and every triangle should keep its counter clockwise order in UV space, otherwise the texture would be mirrored.
+fn assert_uvs_not_mirrored(shape_data: &ShapeData) {
+    assert_eq!(shape_data.uvs.len() / 2, shape_data.nr_of_vertices());
+    assert!(shape_data.uvs.iter().all(|&uv| (-1e-6..=1.0 + 1e-6).contains(&uv)));
+    for triangle in shape_data.indices.chunks(3) {
+        let uv = |i: u32| [shape_data.uvs[i as usize * 2], shape_data.uvs[i as usize * 2 + 1]];
+        let (a, b, c) = (uv(triangle[0]), uv(triangle[1]), uv(triangle[2]));
+        let area = (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0]);
+        assert!(area > 0.0);
+    }
+}
+
+#[wasm_bindgen_test]
+fn uvs() {
+    assert_uvs_not_mirrored(&generate_geometry(&ShapeParams::Plane { subdivisions: 3 }, vec![1.0, 1.0, 1.0]).unwrap());
+    assert_uvs_not_mirrored(&generate_geometry(&ShapeParams::Disk { sides: 10, radius: 1.0, cap_segments: 1, start_angle: 0.0, sweep_angle: 360.0 }, vec![1.0, 1.0, 1.0]).unwrap());
+    assert_uvs_not_mirrored(&generate_geometry(&ShapeParams::Cube { subdivisions: 3 }, vec![1.0, 1.0, 1.0]).unwrap());
+    assert_uvs_not_mirrored(&generate_geometry(&ShapeParams::Sphere { subdivisions: 3, mode: SphereMode::Cube, smooth: true }, vec![1.0, 1.0, 1.0]).unwrap());
+    assert_uvs_not_mirrored(&generate_geometry(&ShapeParams::Cylinder { sides: 12, radius: 1.0, height_segments: 1, cap_segments: 1, start_angle: 0.0, sweep_angle: 360.0, side_caps: true, smooth: true }, vec![1.0, 1.0, 1.0]).unwrap());
+    assert_uvs_not_mirrored(&generate_geometry(&ShapeParams::Tube { sides: 12, inner_radius: 0.8, outer_radius: 1.0, height_segments: 1, cap_segments: 1, start_angle: 0.0, sweep_angle: 360.0, side_caps: true, smooth: true }, vec![1.0, 1.0, 1.0]).unwrap());
+}
+
+#[wasm_bindgen_test]
+fn stl() {
+    let cube = generate_geometry(&ShapeParams::Cube { subdivisions: 1 }, vec![1.0, 1.0, 1.0]).unwrap();
+
+    let binary = stl_binary(&cube);
+    assert_eq!(binary.len(), 84 + 12 * 50);
+    assert_eq!(u32::from_le_bytes([binary[80], binary[81], binary[82], binary[83]]), 12);
+    // The first triangle lies on the front (+Z) side so its facet normal is 0, 0, 1.
+    let normal_z = f32::from_le_bytes([binary[92], binary[93], binary[94], binary[95]]);
+    assert_eq!(normal_z, 1.0);
+
+    let ascii = stl_ascii(&cube, "cube");
+    assert!(ascii.starts_with("solid cube\n"));
+    assert!(ascii.ends_with("endsolid cube\n"));
+    assert_eq!(ascii.matches("facet normal").count(), 12);
+    assert_eq!(ascii.matches("vertex").count(), 36);
+
+    assert_eq!(export_shape_stl(&ShapeParams::Cube { subdivisions: 1 }, vec![1.0, 1.0, 1.0], true).unwrap(), binary);
+}
+
+#[wasm_bindgen_test]
+fn gltf() {
+    let plane = generate_geometry(&ShapeParams::Plane { subdivisions: 1 }, vec![1.0, 1.0, 1.0]).unwrap();
+
+    let embedded = gltf_embedded(&plane, "Plane").unwrap();
+    assert!(embedded.contains(r#""version":"2.0""#));
+    assert!(embedded.contains(r#""nodes":[{"mesh":0,"name":"Plane"}]"#));
+    assert!(embedded.contains(r#""meshes":[{"name":"Plane","#));
+    assert!(embedded.contains(r#""min":[-0.5,-0.5,0],"max":[0.5,0.5,0]"#));
+    assert!(embedded.contains("data:application/octet-stream;base64,"));
+
+    // 4 vertices of 8 floats and 6 indices.
+    let glb = glb(&plane, "Plane").unwrap();
+    assert_eq!(&glb[0..4], b"glTF");
+    assert_eq!(u32::from_le_bytes([glb[8], glb[9], glb[10], glb[11]]) as usize, glb.len());
+    let json_length = u32::from_le_bytes([glb[12], glb[13], glb[14], glb[15]]) as usize;
+    assert_eq!(json_length % 4, 0);
+    let bin_length = u32::from_le_bytes([glb[20 + json_length], glb[21 + json_length], glb[22 + json_length], glb[23 + json_length]]);
+    assert_eq!(bin_length, 4 * 8 * 4 + 6 * 4);
+
+    // The export names the mesh after the shape.
+    let cube = String::from_utf8(export_shape_gltf(&ShapeParams::Cube { subdivisions: 1 }, vec![1.0, 1.0, 1.0], false).unwrap()).unwrap();
+    assert!(cube.contains(r#""meshes":[{"name":"Cube","#));
+
+    // An empty shape has no bounding box for the position accessor.
+    assert_eq!(gltf_embedded(&ShapeData::new(), "Empty").unwrap_err(), ShapeError::EmptyShape);
+}
+
+#[wasm_bindgen_test]
+fn ply() {
+    let plane = generate_geometry(&ShapeParams::Plane { subdivisions: 1 }, vec![1.0, 1.0, 1.0]).unwrap();
+    let colors = [255u8, 0, 0].repeat(plane.nr_of_vertices());
+
+    let ascii = ply_ascii(&plane, true, Some(&colors));
+    assert!(ascii.starts_with("ply\nformat ascii 1.0\n"));
+    assert!(ascii.contains("element vertex 4\n"));
+    assert!(ascii.contains("property float nx\n"));
+    assert!(ascii.contains("property uchar red\n"));
+    assert!(ascii.contains("element face 2\n"));
+    assert!(ascii.contains("-0.5 -0.5 0 0 0 1 255 0 0\n"));
+
+    // Positions and normals are 24 bytes per vertex, colors 3 and every face is 1 + 3 * 4 bytes.
+    let binary = ply_binary(&plane, true, Some(&colors));
+    let header_length = binary.windows(11).position(|w| w == b"end_header\n").unwrap() + 11;
+    assert_eq!(binary.len() - header_length, 4 * 27 + 2 * 13);
+
+    // The alternating colors split the triangles so every vertex belongs to a single triangle.
+    let alternating = export_shape_ply(&ShapeParams::Plane { subdivisions: 1 }, vec![1.0, 1.0, 1.0], false, false, ColorScheme::Alternating, vec![]).unwrap();
+    let alternating = String::from_utf8(alternating).unwrap();
+    assert!(alternating.contains("element vertex 6\n"));
+    assert_eq!(alternating.matches(" 192 192 192\n").count(), 3);
+    assert_eq!(alternating.matches(" 128 128 128\n").count(), 3);
+}
+
+#[wasm_bindgen_test]
+fn obj_export() {
+    let options = ObjOptions {
+        name: String::from("Cube"),
+        precision: 2,
+        material_file: Some(String::from("cube.mtl")),
+    };
+    let data = obj(&generate_geometry(&ShapeParams::Cube { subdivisions: 1 }, vec![1.0, 1.0, 1.0]).unwrap(), &options);
+
+    assert!(data.contains("mtllib cube.mtl\n"));
+    assert!(data.contains("o Cube\n"));
+    assert!(data.contains("usemtl Default\n"));
+    assert!(data.contains("v -0.50 -0.50 0.50\n"));
+    // A cube only needs 8 positions and 6 normals.
+    assert_eq!(data.matches("\nv ").count(), 8);
+    assert_eq!(data.matches("\nvn ").count(), 6);
+    assert_eq!(data.matches("\nf ").count(), 12);
+    for group in ["front", "back", "top", "bottom", "right", "left"].iter() {
+        assert!(data.contains(&format!("g {}\n", group)));
+    }
+
+    let cylinder = export_shape(&ShapeParams::Cylinder { sides: 8, radius: 1.0, height_segments: 1, cap_segments: 1, start_angle: 0.0, sweep_angle: 360.0, side_caps: true, smooth: true }, vec![1.0, 1.0, 1.0], 6, String::new()).unwrap();
+    assert!(!cylinder.contains("mtllib"));
+    assert!(cylinder.contains("g top\n") && cylinder.contains("g side\n") && cylinder.contains("g bottom\n"));
+    assert!(cylinder.lines().filter(|line| line.starts_with("f ")).all(|line| line.split(' ').skip(1).all(|corner| corner.split('/').count() == 3)));
+
+    assert!(mtl().contains("newmtl Default\n"));
+}
+
+#[wasm_bindgen_test]
+fn obj_import() {
+    // A concave L shaped face with negative indices and no normals.
+    let text = "o shape\nv 0 0 0\nv 2 0 0\nv 2 1 0\nv 1 1 0\nv 1 2 0\nv 0 2 0\ng floor\nf -6 -5 -4 -3 -2 -1\n";
+    let shape_data = parse_obj(text).unwrap();
+    assert_eq!(shape_data.nr_of_vertices(), 6);
+    assert_eq!(shape_data.nr_of_triangles(), 4);
+    assert_normals_match_winding(&shape_data);
+    assert_eq!(shape_data.groups.len(), 1);
+    assert_eq!(shape_data.groups[0].name, "floor");
+
+    // Exported shapes can be read back in.
+    let cylinder = generate_geometry(&ShapeParams::Cylinder { sides: 8, radius: 1.0, height_segments: 1, cap_segments: 1, start_angle: 0.0, sweep_angle: 360.0, side_caps: true, smooth: true }, vec![1.0, 1.0, 1.0]).unwrap();
+    let imported = parse_obj(&obj(&cylinder, &ObjOptions::default())).unwrap();
+    assert_eq!(imported.nr_of_triangles(), cylinder.nr_of_triangles());
+    assert_eq!(imported.groups.len(), 3);
+    assert_normals_match_winding(&imported);
+
+    let error = parse_obj("v 0 0 0\nv 1 0 0\n\nf 1 2 3\n").unwrap_err();
+    assert_eq!(error.line, 4);
+    assert_eq!(parse_obj("v 0 zero 0\n").unwrap_err().to_string(), "line 1: 'zero' is not a number");
+}
+
+#[wasm_bindgen_test]
+fn errors() {
+    let scale = || vec![1.0, 1.0, 1.0];
+
+    assert_eq!(usize2Shape(99).unwrap_err(), ShapeError::UnknownShape(99));
+    assert_eq!(generate_geometry(&Shape::Plane.default_params(), vec![1.0, 1.0]).unwrap_err(), ShapeError::MissingArgument("scale"));
+    assert_eq!(generate_geometry(&Shape::Plane.default_params(), vec![1.0, 0.0, 1.0]).unwrap_err(), ShapeError::InvalidScale(0.0));
+    assert_eq!(generate_geometry(&Shape::Plane.default_params(), vec![1.0, 1.0, f32::INFINITY]).unwrap_err(), ShapeError::InvalidScale(f32::INFINITY));
+    assert_eq!(params(Shape::Disk, &[8.0]).unwrap_err(), ShapeError::MissingArgument("radius"));
+
+    // Less than one subdivision or fewer than 3 sides is refused instead of giving an empty or broken shape, and so is a count above the max that would give a mesh too large to make.
+    let invalid_count = |shape: Shape, values: &[f32]| matches!(params(shape, values), Err(ShapeError::InvalidCount { .. }));
+    assert!(invalid_count(Shape::Plane, &[0.10]));
+    assert!(invalid_count(Shape::Disk, &[0.1, 0.10, 1.0, 0.0, 360.0]));
+    assert!(invalid_count(Shape::Cube, &[0.10]));
+    assert!(invalid_count(Shape::Sphere, &[0.10, 0.0, 1.0]));
+    assert!(invalid_count(Shape::Cylinder, &[0.1, 0.10, 1.0, 1.0, 0.0, 360.0, 1.0, 1.0]));
+    assert!(invalid_count(Shape::Tube, &[0.10, 0.8, 1.0, 1.0, 1.0, 0.0, 360.0, 1.0, 1.0]));
+    assert!(invalid_count(Shape::Disk, &[2.0, 1.0, 1.0, 0.0, 360.0]));
+    assert!(invalid_count(Shape::Plane, &[1e9]));
+    assert!(invalid_count(Shape::Sphere, &[f32::INFINITY, 0.0, 1.0]));
+    assert!(params(Shape::Plane, &[256.0]).is_ok());
+
+    assert_eq!(params(Shape::Cube, &[0.0]).unwrap_err(), ShapeError::InvalidCount { name: "subdivisions", value: 0.0, min: 1.0, max: 256.0 });
+    assert_eq!(params(Shape::Cylinder, &[8.0, -1.0, 1.0, 1.0, 0.0, 360.0, 1.0, 1.0]).unwrap_err(), ShapeError::InvalidRadius("radius", -1.0));
+    assert_eq!(params(Shape::Cylinder, &[8.0, f32::INFINITY, 1.0, 1.0, 0.0, 360.0, 1.0, 1.0]).unwrap_err(), ShapeError::InvalidRadius("radius", f32::INFINITY));
+    assert_eq!(params(Shape::Tube, &[8.0, 1.0, 1.0, 1.0, 1.0, 0.0, 360.0, 1.0, 1.0]).unwrap_err(), ShapeError::InnerRadiusTooLarge { inner: 1.0, outer: 1.0 });
+    assert!(matches!(params(Shape::Sphere, &[f32::NAN, 0.0, 1.0]), Err(ShapeError::InvalidCount { name: "subdivisions", .. })));
+
+    // Every count is allowed on its own, but together they can multiply to more vertices than fit in memory.
+    let torus = |radial_segments: usize, smooth: bool| ShapeParams::Torus { major_radius: 1.0, minor_radius: 0.25, radial_segments, tubular_segments: 1024, arc: 360.0, smooth };
+    assert!(matches!(generate_geometry(&torus(1024, false), scale()), Err(ShapeError::TooManyVertices { max: MAX_VERTICES, .. })));
+    assert!(generate_geometry(&torus(64, false), scale()).is_ok());
+    let plane = ShapeParams::Plane { subdivisions: 256 };
+    assert!(plane.max_vertices() <= MAX_VERTICES as f64);
+    assert!(generate_geometry(&plane, scale()).unwrap().nr_of_vertices() as f64 <= plane.max_vertices());
+
+    // Parameters made by hand are checked as well.
+    let tube = ShapeParams::Tube { sides: 8, inner_radius: 2.0, outer_radius: 1.0, height_segments: 1, cap_segments: 1, start_angle: 0.0, sweep_angle: 360.0, side_caps: true, smooth: true };
+    assert_eq!(generate_geometry(&tube, scale()).unwrap_err(), ShapeError::InnerRadiusTooLarge { inner: 2.0, outer: 1.0 });
+    assert_eq!(export_shape(&ShapeParams::Disk { sides: 0, radius: 1.0, cap_segments: 1, start_angle: 0.0, sweep_angle: 360.0 }, scale(), 6, String::new()).unwrap_err(), ShapeError::InvalidCount { name: "sides", value: 0.0, min: 3.0, max: 1024.0 });
+    assert!(export_shape_ply(&Shape::Cube.default_params(), scale(), false, false, ColorScheme::Solid, vec![255]).is_err());
+
+    // More decimals than an f32 has would only pad every value with zeros.
+    assert!(export_shape(&Shape::Cube.default_params(), scale(), MAX_PRECISION, String::new()).is_ok());
+    assert_eq!(export_shape(&Shape::Cube.default_params(), scale(), 1_000_000_000, String::new()).unwrap_err(), ShapeError::InvalidCount { name: "precision", value: 1e9, min: 0.0, max: 9.0 });
+}
+
+#[wasm_bindgen_test]
+fn schema() {
+    for shape in (0..).map_while(|index| usize2Shape(index).ok()) {
+        // The defaults make a valid shape and the values come back in the order of the schema.
+        let defaults = shape.default_params();
+        assert_eq!(defaults.shape(), shape);
+        assert!(generate_geometry(&defaults, vec![1.0, 1.0, 1.0]).is_ok());
+        assert_eq!(params(shape, &defaults.values()).unwrap(), defaults);
+
+        for info in shape.params() {
+            assert!(info.min <= info.default && info.default <= info.max, "{} {}", shape.name(), info.name);
+        }
+    }
+
+    assert_eq!(
+        params(Shape::Tube, &[12.0, 0.5, 2.0, 1.0, 1.0, 0.0, 360.0, 1.0, 0.0]).unwrap(),
+        ShapeParams::Tube { sides: 12, inner_radius: 0.5, outer_radius: 2.0, height_segments: 1, cap_segments: 1, start_angle: 0.0, sweep_angle: 360.0, side_caps: true, smooth: false }
+    );
+}
+
+#[wasm_bindgen_test]
+fn cone() {
+    let cone = ShapeParams::Cone { sides: 12, bottom_radius: 1.0, top_radius: 0.0, height_segments: 3, capped: true, smooth: true };
+    let cone = generate_geometry(&cone, vec![1.0, 1.0, 1.0]).unwrap();
+    assert_faces_outwards(&cone);
+    assert_normals_match_winding(&cone);
+    assert_uvs_not_mirrored(&cone);
+
+    // A pointed cone has no top and no triangles without area at the point.
+    let groups: Vec<&str> = cone.groups.iter().map(|group| group.name.as_str()).collect();
+    assert_eq!(groups, vec!["side", "bottom"]);
+    assert_eq!(cone.nr_of_triangles(), (12 * 2 * 3 - 12) + 12);
+
+    let frustum = ShapeParams::Cone { sides: 8, bottom_radius: 1.0, top_radius: 0.5, height_segments: 1, capped: false, smooth: false };
+    let frustum = generate_geometry(&frustum, vec![1.0, 2.0, 1.0]).unwrap();
+    assert_normals_match_winding(&frustum);
+    assert_eq!(frustum.groups.len(), 1);
+    assert_eq!(frustum.nr_of_triangles(), 8 * 2);
+}
+
+#[wasm_bindgen_test]
+fn torus() {
+    let torus = ShapeParams::Torus { major_radius: 1.0, minor_radius: 0.25, radial_segments: 16, tubular_segments: 8, arc: 360.0, smooth: true };
+    let torus = generate_geometry(&torus, vec![1.0, 1.0, 1.0]).unwrap();
+    assert_normals_match_winding(&torus);
+    assert_uvs_not_mirrored(&torus);
+    assert_eq!(torus.nr_of_vertices(), 17 * 9);
+    assert_eq!(torus.nr_of_triangles(), 16 * 8 * 2);
+
+    // Every vertex lies at the minor radius from the circle around the Y axis.
+    for vertex in torus.vertices.chunks(3) {
+        let distance = (vertex[0] * vertex[0] + vertex[2] * vertex[2]).sqrt() - 1.0;
+        assert!(((distance * distance + vertex[1] * vertex[1]).sqrt() - 0.25).abs() < 1e-5);
+    }
+
+    // The arc starts at +X and turns towards +Z, so half a torus stays on the +Z side.
+    let half = ShapeParams::Torus { major_radius: 1.0, minor_radius: 0.25, radial_segments: 8, tubular_segments: 8, arc: 180.0, smooth: false };
+    let half = generate_geometry(&half, vec![1.0, 1.0, 1.0]).unwrap();
+    assert_normals_match_winding(&half);
+    assert!(half.vertices.chunks(3).all(|vertex| vertex[2] > -1e-5));
+
+    assert!(matches!(params(Shape::Torus, &[1.0, 0.25, 16.0, 8.0, 400.0, 1.0]), Err(ShapeError::InvalidAngle { name: "arc", .. })));
+}
+
+#[wasm_bindgen_test]
+fn sphere_modes() {
+    for mode in [SphereMode::Cube, SphereMode::Uv, SphereMode::Ico] {
+        let sphere = generate_geometry(&ShapeParams::Sphere { subdivisions: 4, mode, smooth: true }, vec![1.0, 1.0, 1.0]).unwrap();
+        assert_faces_outwards(&sphere);
+        assert_normals_match_winding(&sphere);
+        assert_uvs_not_mirrored(&sphere);
+        assert!(sphere.vertices.chunks(3).all(|v| ((v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt() - 1.0).abs() < 1e-5));
+    }
+
+    // 8 rings of 16 sides without the triangles at the poles and 20 faces of 4 * 4 triangles.
+    let uv = generate_geometry(&ShapeParams::Sphere { subdivisions: 4, mode: SphereMode::Uv, smooth: true }, vec![1.0, 1.0, 1.0]).unwrap();
+    assert_eq!(uv.nr_of_triangles(), 8 * 16 * 2 - 2 * 16);
+    let ico = generate_geometry(&ShapeParams::Sphere { subdivisions: 4, mode: SphereMode::Ico, smooth: true }, vec![1.0, 1.0, 1.0]).unwrap();
+    assert_eq!(ico.nr_of_triangles(), 20 * 16);
+
+    assert_eq!(params(Shape::Sphere, &[4.0, 2.0, 1.0]).unwrap(), ShapeParams::Sphere { subdivisions: 4, mode: SphereMode::Ico, smooth: true });
+    assert_eq!(params(Shape::Sphere, &[4.0, 3.0, 1.0]).unwrap_err(), ShapeError::InvalidChoice("mode", 3.0));
+}
+
+#[wasm_bindgen_test]
+fn capsule() {
+    let capsule = ShapeParams::Capsule { radius: 0.5, length: 1.0, radial_segments: 12, cap_rings: 4, smooth: true };
+    let capsule = generate_geometry(&capsule, vec![1.0, 1.0, 1.0]).unwrap();
+    assert_faces_outwards(&capsule);
+    assert_normals_match_winding(&capsule);
+    assert_uvs_not_mirrored(&capsule);
+
+    // Every vertex lies at the radius from the line between the centers of the caps.
+    for vertex in capsule.vertices.chunks(3) {
+        let y = vertex[1] - vertex[1].clamp(-0.5, 0.5);
+        assert!(((vertex[0] * vertex[0] + y * y + vertex[2] * vertex[2]).sqrt() - 0.5).abs() < 1e-5);
+    }
+    let ys = capsule.vertices.chunks(3).map(|vertex| vertex[1]);
+    assert_eq!(ys.clone().fold(f32::MAX, f32::min), -1.0);
+    assert_eq!(ys.fold(f32::MIN, f32::max), 1.0);
+
+    // Each cap has 4 rings of 2 triangles per side without the triangles at the pole.
+    let groups: Vec<(&str, usize)> = capsule.groups.iter().map(|group| (group.name.as_str(), group.count)).collect();
+    assert_eq!(groups, vec![("top", 12 * 7), ("side", 12 * 2), ("bottom", 12 * 7)]);
+
+    assert_eq!(params(Shape::Capsule, &[0.5, -1.0, 12.0, 4.0, 1.0]).unwrap_err(), ShapeError::InvalidLength("length", -1.0));
+}
+
+#[wasm_bindgen_test]
+fn platonic_solids() {
+    let solids = [
+        (Shape::Tetrahedron, "tetrahedron", 4, 3),
+        (Shape::Octahedron, "octahedron", 8, 4),
+        (Shape::Dodecahedron, "dodecahedron", 60, 3),
+        (Shape::Icosahedron, "icosahedron", 20, 5),
+    ];
+
+    for (shape, name, faces, corners_per_vertex) in solids {
+        for spherical in [0.0, 1.0] {
+            let solid = generate_geometry(&params(shape, &[3.0, spherical]).unwrap(), vec![1.0, 1.0, 1.0]).unwrap();
+            assert_faces_outwards(&solid);
+            assert_normals_match_winding(&solid);
+            assert_uvs_not_mirrored(&solid);
+            assert_eq!(solid.nr_of_triangles(), faces * 9);
+            assert_eq!(solid.groups.len(), 1);
+            assert_eq!(solid.groups[0].name, name);
+            assert_eq!(solid.groups[0].count, faces * 9);
+
+            let on_sphere = |v: &[f32]| ((v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt() - 1.0).abs() < 1e-5;
+            if spherical == 1.0 {
+                assert!(solid.vertices.chunks(3).all(on_sphere));
+            } else {
+                // Only the corners of the solid lie on the sphere, every corner is shared by the same number of faces.
+                let count = solid.vertices.chunks(3).filter(|v| on_sphere(v)).count();
+                assert_eq!(count % corners_per_vertex, 0);
+                assert!(count < solid.nr_of_vertices());
+            }
+        }
+    }
+
+    assert_eq!(params(Shape::Octahedron, &[0.0, 0.0]).unwrap_err(), ShapeError::InvalidCount { name: "subdivisions", value: 0.0, min: 1.0, max: 256.0 });
+}
+
+#[wasm_bindgen_test]
+fn rounded_box() {
+    let rounded_box = ShapeParams::RoundedBox { width: 2.0, height: 1.0, depth: 1.0, fillet_radius: 0.25, fillet_segments: 4 };
+    let scale = vec![1.0, 3.0, 1.0];
+    let shape = generate_geometry(&rounded_box, scale.clone()).unwrap();
+    assert_faces_outwards(&shape);
+    assert_normals_match_winding(&shape);
+    assert_uvs_not_mirrored(&shape);
+
+    // The scale sets the size of the box, the fillets keep their radius: every vertex lies at the radius from the inner box, with the normal pointing away from it.
+    let inner = [0.75, 1.25, 0.25];
+    for (vertex, normal) in shape.vertices.chunks(3).zip(shape.normals.chunks(3)) {
+        let offset: Vec<f32> = (0..3).map(|k| vertex[k] - vertex[k].clamp(-inner[k], inner[k])).collect();
+        assert!(((offset[0] * offset[0] + offset[1] * offset[1] + offset[2] * offset[2]).sqrt() - 0.25).abs() < 1e-5);
+        assert!((0..3).all(|k| (offset[k] / 0.25 - normal[k]).abs() < 1e-4));
+    }
+    let extent = |k: usize| shape.vertices.chunks(3).map(|vertex| vertex[k]).fold(f32::MIN, f32::max);
+    assert_eq!([extent(0), extent(1), extent(2)], [1.0, 1.5, 0.5]);
+
+    // Every side has a flat part and 2 fillet segments on both ends, so a grid of 5 by 5 quads.
+    let groups: Vec<(&str, usize)> = shape.groups.iter().map(|group| (group.name.as_str(), group.count)).collect();
+    assert_eq!(groups, ["front", "back", "top", "bottom", "right", "left"].map(|name| (name, 50)));
+
+    // Without a fillet the box is the same as a scaled cube.
+    let cube = generate_geometry(&ShapeParams::Cube { subdivisions: 1 }, scale.clone()).unwrap();
+    let sharp = ShapeParams::RoundedBox { width: 1.0, height: 1.0, depth: 1.0, fillet_radius: 0.0, fillet_segments: 4 };
+    let sharp = generate_geometry(&sharp, scale.clone()).unwrap();
+    assert_eq!(sharp.indices, cube.indices);
+    assert!(sharp.vertices.iter().zip(&cube.vertices).all(|(a, b)| (a - b).abs() < 1e-6));
+    assert_eq!(sharp.normals, cube.normals);
+    assert_eq!(sharp.uvs, cube.uvs);
+
+    // A fillet larger than the box makes a sphere with an odd number of segments splitting the middle one.
+    let round = ShapeParams::RoundedBox { width: 1.0, height: 1.0, depth: 1.0, fillet_radius: 5.0, fillet_segments: 3 };
+    let round = generate_geometry(&round, vec![1.0, 1.0, 1.0]).unwrap();
+    assert_faces_outwards(&round);
+    assert_uvs_not_mirrored(&round);
+    assert!(round.vertices.chunks(3).all(|v| ((v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt() - 0.5).abs() < 1e-5));
+    assert_eq!(round.nr_of_triangles(), 6 * 4 * 4 * 2);
+
+    assert_eq!(params(Shape::RoundedBox, &[1.0, -1.0, 1.0, 0.1, 4.0]).unwrap_err(), ShapeError::InvalidLength("height", -1.0));
+}
+
+// Every edge of a closed mesh should be used once in both directions, counting vertices at the same position as the same vertex so seams don't count as holes.
+fn assert_watertight(shape_data: &ShapeData) {
+    let key = |i: u32| {
+        let i = i as usize * 3;
+        [0, 1, 2].map(|k| (shape_data.vertices[i + k] * 1e4).round() as i32)
+    };
+    let mut edges = std::collections::HashMap::new();
+    for triangle in shape_data.indices.chunks(3) {
+        for k in 0..3 {
+            let (a, b) = (key(triangle[k]), key(triangle[(k + 1) % 3]));
+            *edges.entry((a, b)).or_insert(0) += 1;
+        }
+    }
+    for (&(a, b), &count) in edges.iter() {
+        assert_eq!(edges.get(&(b, a)).copied().unwrap_or(0), count, "{:?} {:?}", a, b);
+    }
+}
+
+#[wasm_bindgen_test]
+fn slices() {
+    let disk = generate_geometry(&params(Shape::Disk, &[6.0, 1.0, 1.0, 90.0, 180.0]).unwrap(), vec![1.0, 1.0, 1.0]).unwrap();
+    assert_eq!(disk.nr_of_vertices(), 8);
+    assert_eq!(disk.nr_of_triangles(), 6);
+    assert_normals_match_winding(&disk);
+    assert_uvs_not_mirrored(&disk);
+    // The slice runs counter clockwise from the Y axis to the negative Y axis, so it covers the left half of the disk.
+    assert!(disk.vertices.chunks(3).all(|vertex| vertex[0] <= 1e-6));
+
+    let cylinder = params(Shape::Cylinder, &[8.0, 1.0, 1.0, 1.0, 30.0, 120.0, 1.0, 1.0]).unwrap();
+    let tube = params(Shape::Tube, &[8.0, 0.5, 1.0, 1.0, 1.0, 30.0, 270.0, 1.0, 1.0]).unwrap();
+    for slice in [cylinder, tube] {
+        let shape = generate_geometry(&slice, vec![1.0, 1.0, 1.0]).unwrap();
+        assert_watertight(&shape);
+        assert_normals_match_winding(&shape);
+        assert_uvs_not_mirrored(&shape);
+        let names: Vec<&str> = shape.groups.iter().map(|group| group.name.as_str()).collect();
+        assert_eq!(&names[names.len() - 2..], ["start", "end"]);
+
+        // Without side caps the cut faces are left open, a full sweep has no cut faces at all.
+        let mut values = slice.values();
+        let side_caps = values.len() - 2;
+        values[side_caps] = 0.0;
+        let open = generate_geometry(&params(slice.shape(), &values).unwrap(), vec![1.0, 1.0, 1.0]).unwrap();
+        assert_eq!(open.nr_of_triangles(), shape.nr_of_triangles() - 4);
+        values[side_caps - 1] = 360.0;
+        values[side_caps] = 1.0;
+        let full = generate_geometry(&params(slice.shape(), &values).unwrap(), vec![1.0, 1.0, 1.0]).unwrap();
+        assert_watertight(&full);
+        assert!(full.groups.iter().all(|group| group.name != "start" && group.name != "end"));
+    }
+
+    // A cylinder with a radius of 0 has cut faces without a width, their UVs still need to be numbers.
+    let line = generate_geometry(&params(Shape::Cylinder, &[8.0, 0.0, 1.0, 1.0, 30.0, 120.0, 1.0, 1.0]).unwrap(), vec![1.0, 1.0, 1.0]).unwrap();
+    assert!(line.uvs.iter().all(|value| value.is_finite()));
+
+    assert_eq!(
+        params(Shape::Cylinder, &[8.0, 1.0, 1.0, 1.0, 0.0, 400.0, 1.0, 1.0]).unwrap_err(),
+        ShapeError::InvalidAngle { name: "sweep_angle", value: 400.0, min: 0.0, max: 360.0 }
+    );
+}
+
+#[wasm_bindgen_test]
+fn segments() {
+    // Every ring after the first one around the center adds a band of 2 triangles per side.
+    let disk = generate_geometry(&params(Shape::Disk, &[10.0, 1.0, 3.0, 0.0, 360.0]).unwrap(), vec![1.0, 1.0, 1.0]).unwrap();
+    assert_eq!(disk.nr_of_vertices(), 1 + 3 * 10);
+    assert_eq!(disk.nr_of_triangles(), 10 + 2 * 2 * 10);
+    assert_normals_match_winding(&disk);
+    assert_uvs_not_mirrored(&disk);
+
+    let cylinder = params(Shape::Cylinder, &[8.0, 1.0, 4.0, 3.0, 0.0, 90.0, 1.0, 1.0]).unwrap();
+    let tube = params(Shape::Tube, &[8.0, 0.5, 1.0, 4.0, 3.0, 0.0, 90.0, 1.0, 1.0]).unwrap();
+    for (shape, walls, cap_bands) in [(cylinder, 1, 8 * (1 + 2 * 2)), (tube, 2, 8 * 2 * 3)] {
+        let shape = generate_geometry(&shape, vec![1.0, 1.0, 1.0]).unwrap();
+        assert_watertight(&shape);
+        assert_normals_match_winding(&shape);
+        assert_uvs_not_mirrored(&shape);
+
+        let count = |name: &str| shape.groups.iter().find(|group| group.name == name).unwrap().count;
+        assert_eq!(count("top"), cap_bands);
+        assert_eq!(count("bottom"), cap_bands);
+        assert_eq!(count("start"), 3 * 4 * 2);
+        let wall = if walls == 1 { count("side") } else { count("outside") + count("inside") };
+        assert_eq!(wall, walls * 8 * 4 * 2);
+
+        // The walls are split into rows of equal height.
+        let mut heights: Vec<i32> = shape.vertices.chunks(3).map(|vertex| (vertex[1] * 1000.0).round() as i32).collect();
+        heights.sort();
+        heights.dedup();
+        assert_eq!(heights, vec![-500, -250, 0, 250, 500]);
+    }
+}
+
+#[wasm_bindgen_test]
+fn ring() {
+    let ring = generate_geometry(&params(Shape::Ring, &[12.0, 0.5, 1.0, 2.0, 0.0, 360.0]).unwrap(), vec![1.0, 1.0, 1.0]).unwrap();
+    assert_eq!(ring.nr_of_vertices(), 3 * 12);
+    assert_eq!(ring.nr_of_triangles(), 2 * 2 * 12);
+    assert_eq!(ring.groups[0].name, "ring");
+    assert_normals_match_winding(&ring);
+    assert_uvs_not_mirrored(&ring);
+
+    // Every vertex lies between the radii in the XY plane, the outer edge touches the edges of the texture and the hole is left out of it.
+    for (vertex, uv) in ring.vertices.chunks(3).zip(ring.uvs.chunks(2)) {
+        let radius = (vertex[0] * vertex[0] + vertex[1] * vertex[1]).sqrt();
+        assert!((0.5 - 1e-5..=1.0 + 1e-5).contains(&radius) && vertex[2] == 0.0);
+        let uv_radius = ((uv[0] - 0.5).powi(2) + (uv[1] - 0.5).powi(2)).sqrt();
+        assert!((uv_radius - radius * 0.5).abs() < 1e-5);
+    }
+
+    // A slice of the ring gets a vertex at both ends of every ring.
+    let arch = generate_geometry(&params(Shape::Ring, &[12.0, 0.5, 1.0, 2.0, 0.0, 180.0]).unwrap(), vec![1.0, 1.0, 1.0]).unwrap();
+    assert_eq!(arch.nr_of_vertices(), 3 * 13);
+    assert_eq!(arch.nr_of_triangles(), 2 * 2 * 12);
+    assert!(arch.vertices.chunks(3).all(|vertex| vertex[1] >= -1e-6));
+
+    assert_eq!(params(Shape::Ring, &[12.0, 1.0, 0.5, 1.0, 0.0, 360.0]).unwrap_err(), ShapeError::InnerRadiusTooLarge { inner: 1.0, outer: 0.5 });
+}
+
+#[wasm_bindgen_test]
+fn prism_and_pyramid() {
+    for twist in [0.0, 90.0] {
+        let prism = generate_geometry(&params(Shape::Prism, &[6.0, 1.0, 2.0, twist, 4.0]).unwrap(), vec![1.0, 1.0, 1.0]).unwrap();
+        assert_faces_outwards(&prism);
+        assert_normals_match_winding(&prism);
+        assert_uvs_not_mirrored(&prism);
+        assert_watertight(&prism);
+        assert_eq!(prism.nr_of_triangles(), 6 + 6 * 4 * 2 + 6);
+
+        // The top is turned by the whole twist, the bottom corner stays on the X axis.
+        let (sin, cos) = twist.to_radians().sin_cos();
+        let has_corner = |corner: [f32; 3]| prism.vertices.chunks(3).any(|vertex| (0..3).all(|k| (vertex[k] - corner[k]).abs() < 1e-5));
+        assert!(has_corner([cos, 1.0, sin]));
+        assert!(has_corner([1.0, -1.0, 0.0]));
+    }
+
+    // Every side of the prism is flat, so every triangle of a side has the same normal at all three corners.
+    let prism = generate_geometry(&params(Shape::Prism, &[3.0, 1.0, 1.0, 0.0, 1.0]).unwrap(), vec![1.0, 1.0, 1.0]).unwrap();
+    for triangle in prism.indices.chunks(3) {
+        let normal = |i: u32| &prism.normals[i as usize * 3..i as usize * 3 + 3];
+        assert_eq!(normal(triangle[0]), normal(triangle[1]));
+        assert_eq!(normal(triangle[0]), normal(triangle[2]));
+    }
+
+    let pyramid = generate_geometry(&params(Shape::Pyramid, &[4.0, 1.0, 1.0, 0.0]).unwrap(), vec![1.0, 1.0, 1.0]).unwrap();
+    assert_faces_outwards(&pyramid);
+    assert_normals_match_winding(&pyramid);
+    assert_uvs_not_mirrored(&pyramid);
+    assert_watertight(&pyramid);
+    assert_eq!(pyramid.nr_of_triangles(), 4 + 4);
+
+    // Cutting off a quarter of the height leaves a top with a quarter of the radius.
+    let frustum = generate_geometry(&params(Shape::Pyramid, &[4.0, 1.0, 2.0, 0.5]).unwrap(), vec![1.0, 1.0, 1.0]).unwrap();
+    assert_faces_outwards(&frustum);
+    assert_watertight(&frustum);
+    assert_eq!(frustum.nr_of_triangles(), 4 + 4 * 2 + 4);
+    let top: Vec<&[f32]> = frustum.vertices.chunks(3).filter(|vertex| vertex[1] == 0.75).collect();
+    assert!(top.iter().all(|vertex| (vertex[0] * vertex[0] + vertex[2] * vertex[2]).sqrt() < 0.25 + 1e-6));
+    assert!(frustum.vertices.chunks(3).all(|vertex| (-0.75..=0.75).contains(&vertex[1])));
+
+    assert_eq!(params(Shape::Pyramid, &[4.0, 1.0, 1.0, 1.0]).unwrap_err(), ShapeError::TruncationTooLarge { truncation: 1.0, height: 1.0 });
+}
+
+#[wasm_bindgen_test]
+fn lathe() {
+    let lathe = |profile: &[f32], sweep_angle: f32| ShapeParams::Lathe { profile: profile.to_vec(), radial_segments: 16, start_angle: 0.0, sweep_angle, smooth: true };
+
+    // The default profile is a vase with a closed bottom and an open top.
+    let vase = generate_geometry(&Shape::Lathe.default_params(), vec![1.0, 1.0, 1.0]).unwrap();
+    assert_normals_match_winding(&vase);
+    assert_uvs_not_mirrored(&vase);
+    assert_eq!(vase.nr_of_triangles(), 24 * (1 + 2 * 5));
+
+    // A profile around the side and over both caps gives a closed cylinder with the same outline as cylinder().
+    let closed = generate_geometry(&lathe(&[0.0, -0.5, 1.0, -0.5, 1.0, 0.5, 0.0, 0.5], 360.0), vec![1.0, 1.0, 1.0]).unwrap();
+    assert_faces_outwards(&closed);
+    assert_normals_match_winding(&closed);
+    assert_uvs_not_mirrored(&closed);
+    assert_watertight(&closed);
+    let cylinder = generate_geometry(&params(Shape::Cylinder, &[16.0, 1.0, 1.0, 1.0, 0.0, 360.0, 1.0, 1.0]).unwrap(), vec![1.0, 1.0, 1.0]).unwrap();
+    assert_eq!(closed.nr_of_triangles(), cylinder.nr_of_triangles());
+
+    // The normal of a point lies between the parts of the profile around it, at the rim of the cap it points outwards and up.
+    let rim = closed.vertices.chunks(3).zip(closed.normals.chunks(3)).find(|(vertex, _)| vertex[0] == 1.0 && vertex[1] == 0.5).unwrap().1;
+    assert!((rim[0] - 0.5f32.sqrt()).abs() < 1e-5 && (rim[1] - 0.5f32.sqrt()).abs() < 1e-5);
+
+    // Half a turn only covers the side of the Y axis the angle turns towards.
+    let half = generate_geometry(&lathe(&[0.5, -0.5, 0.5, 0.5], 180.0), vec![1.0, 1.0, 1.0]).unwrap();
+    assert_normals_match_winding(&half);
+    assert_eq!(half.nr_of_vertices(), 2 * 17);
+    assert!(half.vertices.chunks(3).all(|vertex| vertex[2] >= -1e-6));
+
+    let invalid = |profile: &[f32]| generate_geometry(&lathe(profile, 360.0), vec![1.0, 1.0, 1.0]).unwrap_err();
+    assert_eq!(invalid(&[0.0, 0.0, 1.0]), ShapeError::InvalidProfile("needs a radius and a height for every point"));
+    assert_eq!(invalid(&[0.0, 0.0]), ShapeError::InvalidProfile("needs at least 2 points"));
+    assert_eq!(invalid(&[0.0, 0.0, f32::NAN, 1.0]), ShapeError::InvalidProfile("can only hold finite numbers"));
+    assert_eq!(invalid(&[0.0, 0.0, -1.0, 1.0]), ShapeError::InvalidProfile("can't have a radius below 0"));
+}
+
+#[wasm_bindgen_test]
+fn extrude() {
+    let extrude = |outline: &[f32], holes: &[&[f32]], depth: f32, bevel_size: f32| ShapeParams::Extrude {
+        outline: outline.to_vec(),
+        holes: holes.iter().map(|hole| hole.to_vec()).collect(),
+        depth,
+        bevel_size,
+        bevel_segments: 3,
+    };
+    let front_area = |shape_data: &ShapeData| -> f32 {
+        shape_data.indices.chunks(3).map(|triangle| {
+            let corner = |k: usize| &shape_data.vertices[triangle[k] as usize * 3..triangle[k] as usize * 3 + 3];
+            let (a, b, c) = (corner(0), corner(1), corner(2));
+            let normal = &shape_data.normals[triangle[0] as usize * 3..triangle[0] as usize * 3 + 3];
+            if normal[2] == 1.0 { ((b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])) / 2.0 } else { 0.0 }
+        }).sum()
+    };
+
+    // The default is a square frame with a bevel of 3 steps on both sides of every edge.
+    let frame = generate_geometry(&Shape::Extrude.default_params(), vec![1.0, 1.0, 1.0]).unwrap();
+    assert_normals_match_winding(&frame);
+    assert_uvs_not_mirrored(&frame);
+    assert_watertight(&frame);
+    assert_eq!(frame.nr_of_triangles(), 2 * 8 + 8 * 7 * 2);
+    assert_eq!(frame.groups.iter().map(|group| group.name.as_str()).collect::<Vec<_>>(), ["front", "side", "back"]);
+
+    // The inside of the frame faces the hole, a solid block faces outwards all around.
+    let square: &[f32] = &[-1.0, -1.0, 1.0, -1.0, 1.0, 1.0, -1.0, 1.0];
+    let block = generate_geometry(&extrude(square, &[], 1.0, 0.25), vec![1.0, 1.0, 1.0]).unwrap();
+    assert_faces_outwards(&block);
+    assert_watertight(&block);
+
+    // Without a bevel the front covers the outline except for the holes, whichever way the polygons run.
+    let holes: [&[f32]; 2] = [&[-0.75, -0.5, -0.25, -0.5, -0.25, 0.5, -0.75, 0.5], &[0.75, 0.5, 0.75, -0.5, 0.25, -0.5, 0.25, 0.5]];
+    let plate = generate_geometry(&extrude(square, &holes, 0.5, 0.0), vec![1.0, 1.0, 1.0]).unwrap();
+    assert_normals_match_winding(&plate);
+    assert_uvs_not_mirrored(&plate);
+    assert_watertight(&plate);
+    assert!((front_area(&plate) - (4.0 - 2.0 * 0.5)).abs() < 1e-5);
+    assert!(plate.vertices.chunks(3).all(|vertex| vertex[2].abs() == 0.25));
+
+    // A concave outline with a bevel as deep as the extrusion is still closed, the bevel is limited to half the depth and pulls the caps inwards by as much.
+    let l_shape: &[f32] = &[0.0, 0.0, 2.0, 0.0, 2.0, 1.0, 1.0, 1.0, 1.0, 2.0, 0.0, 2.0];
+    let rounded = generate_geometry(&extrude(l_shape, &[], 0.2, 0.5), vec![1.0, 1.0, 1.0]).unwrap();
+    assert_normals_match_winding(&rounded);
+    assert_uvs_not_mirrored(&rounded);
+    assert_watertight(&rounded);
+    assert!(rounded.vertices.chunks(3).all(|vertex| vertex[2].abs() <= 0.1 + 1e-6));
+    assert!((front_area(&rounded) - (1.8 * 0.8 + 0.8 * 1.0)).abs() < 1e-5);
+
+    let invalid = |outline: &[f32], holes: &[&[f32]]| generate_geometry(&extrude(outline, holes, 1.0, 0.0), vec![1.0, 1.0, 1.0]).unwrap_err();
+    assert_eq!(invalid(&[0.0, 0.0, 1.0, 0.0, 1.0], &[]), ShapeError::InvalidPolygon { name: "outline", reason: "need an x and a y for every corner" });
+    assert_eq!(invalid(&[0.0, 0.0, 1.0, 0.0], &[]), ShapeError::InvalidPolygon { name: "outline", reason: "need at least 3 corners" });
+    assert_eq!(invalid(square, &[&[0.0, 0.0, f32::NAN, 0.0, 0.0, 0.5]]), ShapeError::InvalidPolygon { name: "holes", reason: "can only hold finite numbers" });
+
+    // A bevel larger than half a wall would pull the caps across each other, or turn a narrow part of the polygon inside out.
+    let too_thin = ShapeError::InvalidPolygon { name: "outline", reason: "is too thin for the bevel" };
+    assert!(generate_geometry(&extrude(&DEFAULT_OUTLINE, &[&DEFAULT_HOLE], 1.0, 0.1), vec![1.0, 1.0, 1.0]).is_ok());
+    assert_eq!(generate_geometry(&extrude(&DEFAULT_OUTLINE, &[&DEFAULT_HOLE], 1.0, 0.3), vec![1.0, 1.0, 1.0]).unwrap_err(), too_thin);
+    assert_eq!(generate_geometry(&extrude(&[0.0, 0.0, 2.0, 0.0, 2.0, 0.2, 0.0, 0.2], &[], 1.0, 0.15), vec![1.0, 1.0, 1.0]).unwrap_err(), too_thin);
+}
+
+#[wasm_bindgen_test]
+fn sweep() {
+    let sweep = |path: &[f32], section: &[f32], scales: &[f32], twist: f32, path_segments: usize| ShapeParams::Sweep {
+        path: path.to_vec(),
+        section: section.to_vec(),
+        scales: scales.to_vec(),
+        twists: Vec::new(),
+        sides: 16,
+        radius: 1.0,
+        path_segments,
+        twist,
+        capped: true,
+        smooth: true,
+    };
+    let groups = |shape_data: &ShapeData| shape_data.groups.iter().map(|group| group.name.clone()).collect::<Vec<_>>();
+
+    // The default is a round arch with both ends closed.
+    let arch = generate_geometry(&Shape::Sweep.default_params(), vec![1.0, 1.0, 1.0]).unwrap();
+    assert_normals_match_winding(&arch);
+    assert_uvs_not_mirrored(&arch);
+    assert_watertight(&arch);
+    assert_eq!(arch.nr_of_triangles(), (4 * 8) * 12 * 2 + 2 * 10);
+    assert_eq!(groups(&arch), ["start", "side", "end"]);
+
+    // A straight path along the Y axis gives a closed cylinder.
+    let straight = generate_geometry(&sweep(&[0.0, -0.5, 0.0, 0.0, 0.5, 0.0], &[], &[], 0.0, 1), vec![1.0, 1.0, 1.0]).unwrap();
+    assert_faces_outwards(&straight);
+    assert_normals_match_winding(&straight);
+    assert_watertight(&straight);
+    assert_eq!(straight.nr_of_triangles(), 16 * 2 + 2 * 14);
+    assert!(straight.vertices.chunks(3).all(|vertex| (vertex[0] * vertex[0] + vertex[2] * vertex[2]).sqrt() < 1.0 + 1e-5));
+
+    // The twist turns the corners of the section at the end by a quarter turn against the start.
+    let square: &[f32] = &[-0.5, -0.5, 0.5, -0.5, 0.5, 0.5, -0.5, 0.5];
+    let twisted = generate_geometry(&sweep(&[0.0, 0.0, 0.0, 0.0, 0.0, 2.0], square, &[], 45.0, 4), vec![1.0, 1.0, 1.0]).unwrap();
+    assert_normals_match_winding(&twisted);
+    assert_watertight(&twisted);
+    let corners_at = |z: f32| twisted.vertices.chunks(3).filter(|vertex| vertex[2] == z).map(|vertex| vertex[0].abs().max(vertex[1].abs())).fold(0.0, f32::max);
+    assert!((corners_at(0.0) - 0.5).abs() < 1e-5);
+    assert!((corners_at(2.0) - 0.5f32.sqrt()).abs() < 1e-5);
+
+    // A path that ends where it starts is closed without caps, even when it doesn't lie flat. A square corner keeps the width of the section.
+    let knot = [0.0, 0.0, 0.0, 8.0, 0.0, 0.0, 8.0, 2.0, 8.0, 0.0, 6.0, 8.0, 0.0, 0.0, 0.0];
+    let closed = generate_geometry(&sweep(&knot, &[], &[], 0.0, 4), vec![1.0, 1.0, 1.0]).unwrap();
+    assert_normals_match_winding(&closed);
+    assert_watertight(&closed);
+    assert_eq!(groups(&closed), ["side"]);
+    let frame = generate_geometry(&sweep(&[0.0, 0.0, 0.0, 4.0, 0.0, 0.0, 4.0, 0.0, 4.0, 0.0, 0.0, 4.0, 0.0, 0.0, 0.0], square, &[], 0.0, 1), vec![1.0, 1.0, 1.0]).unwrap();
+    assert_watertight(&frame);
+    assert!(frame.vertices.chunks(3).all(|vertex| [vertex[0], vertex[2]].iter().all(|&value| (-0.5 - 1e-5..=0.5 + 1e-5).contains(&value) || (3.5 - 1e-5..=4.5 + 1e-5).contains(&value))));
+
+    // A section that shrinks to a point ends in a tip without a cap.
+    let horn = generate_geometry(&sweep(&[0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 1.0, 2.0, 0.0], &[], &[1.0, 0.5, 0.0], 0.0, 4), vec![1.0, 1.0, 1.0]).unwrap();
+    assert_normals_match_winding(&horn);
+    assert_watertight(&horn);
+    assert_eq!(groups(&horn), ["start", "side"]);
+
+    // A radius of 0 gives a section without a length around it, the UVs still need to be numbers.
+    let line = generate_geometry(&params(Shape::Sweep, &[12.0, 0.0, 8.0, 0.0, 1.0, 1.0]).unwrap(), vec![1.0, 1.0, 1.0]).unwrap();
+    assert!(line.uvs.iter().all(|value| value.is_finite()));
+
+    let invalid = |path: &[f32], scales: &[f32]| generate_geometry(&sweep(path, &[], scales, 0.0, 1), vec![1.0, 1.0, 1.0]).unwrap_err();
+    assert_eq!(invalid(&[0.0, 0.0, 0.0, 1.0], &[]), ShapeError::InvalidPath("needs an x, a y and a z for every point"));
+    assert_eq!(invalid(&[0.0, 0.0, 0.0], &[]), ShapeError::InvalidPath("needs at least 2 points"));
+    assert_eq!(invalid(&[1.0, 2.0, 3.0, 1.0, 2.0, 3.0, 1.0, 2.0, 3.0], &[]), ShapeError::InvalidPath("needs at least 2 distinct points"));
+    assert_eq!(invalid(&[0.0, 0.0, 0.0, 0.0, 1e-9, 0.0], &[]), ShapeError::InvalidPath("needs at least 2 distinct points"));
+    assert_eq!(invalid(&[0.0, 0.0, 0.0, 0.0, 1.0, 0.0], &[1.0]), ShapeError::InvalidPath("needs a scale for every point"));
+    assert_eq!(invalid(&[0.0, 0.0, 0.0, 0.0, 1.0, 0.0], &[1.0, -1.0]), ShapeError::InvalidPath("can't have a scale below 0"));
+}
+
+#[wasm_bindgen_test]
+fn terrain() {
+    let terrain = |heightmap: &[u8], heightmap_width: usize, subdivisions: [usize; 2], seed: usize| ShapeParams::Terrain {
+        heightmap: heightmap.to_vec(),
+        heightmap_width,
+        x_subdivisions: subdivisions[0],
+        y_subdivisions: subdivisions[1],
+        height: 0.5,
+        seed,
+        frequency: 4.0,
+        octaves: 5,
+        lacunarity: 2.0,
+        gain: 0.5,
+        smooth: true,
+    };
+
+    let hills = generate_geometry(&Shape::Terrain.default_params(), vec![1.0, 1.0, 1.0]).unwrap();
+    assert_normals_match_winding(&hills);
+    assert_uvs_not_mirrored(&hills);
+    assert_eq!(hills.nr_of_triangles(), 64 * 64 * 2);
+    assert!(hills.vertices.chunks(3).all(|vertex| (0.0..=0.25).contains(&vertex[2])));
+    assert!(hills.vertices.chunks(3).any(|vertex| vertex[2] != hills.vertices[2]));
+
+    // The same seed always gives the same terrain, another seed gives another one.
+    let noise = |seed: usize| generate_geometry(&terrain(&[], 0, [8, 4], seed), vec![1.0, 1.0, 1.0]).unwrap();
+    assert_eq!(noise(7).vertices, noise(7).vertices);
+    assert_ne!(noise(7).vertices, noise(8).vertices);
+    assert_eq!(noise(7).nr_of_vertices(), 9 * 5);
+    assert_eq!(noise(7).nr_of_triangles(), 8 * 4 * 2);
+    assert_uvs_not_mirrored(&noise(7));
+
+    // A heightmap that runs from black at the top to white at the bottom gives an even slope down to the top.
+    let slope = generate_geometry(&terrain(&[0, 0, 255, 255], 2, [4, 4], 1), vec![1.0, 1.0, 1.0]).unwrap();
+    assert_normals_match_winding(&slope);
+    for (vertex, normal) in slope.vertices.chunks(3).zip(slope.normals.chunks(3)) {
+        assert!((vertex[2] - 0.5 * (0.5 - vertex[1])).abs() < 1e-6);
+        let length = (0.5f32 * 0.5 + 1.0).sqrt();
+        assert!(normal[0].abs() < 1e-6 && (normal[1] - 0.5 / length).abs() < 1e-5 && (normal[2] - 1.0 / length).abs() < 1e-5);
+    }
+
+    let invalid = |heightmap: &[u8], heightmap_width: usize| generate_geometry(&terrain(heightmap, heightmap_width, [1, 1], 1), vec![1.0, 1.0, 1.0]).unwrap_err();
+    assert_eq!(invalid(&[0, 0, 0], 2), ShapeError::InvalidHeightmap("needs the same number of pixels in every row"));
+    assert_eq!(invalid(&[0], 0), ShapeError::InvalidHeightmap("needs a width of at least 1"));
+}
+
+#[wasm_bindgen_test]
+fn displacement() {
+    let displaced = |base: ShapeParams, seed: usize, amplitude: f32| {
+        let displacement = Displacement { seed, amplitude, frequency: 2.0, octaves: 4 };
+        generate_geometry(&ShapeParams::Displaced { base: Box::new(base), displacement }, vec![1.0, 1.0, 1.0]).unwrap()
+    };
+
+    // A sphere becomes an asteroid that stays within the amplitude of the sphere, the same seed always gives the same asteroid.
+    for mode in [SphereMode::Cube, SphereMode::Uv, SphereMode::Ico] {
+        let asteroid = displaced(ShapeParams::Sphere { subdivisions: 8, mode, smooth: true }, 3, 0.2);
+        assert_normals_match_winding(&asteroid);
+        assert_uvs_not_mirrored(&asteroid);
+        assert_watertight(&asteroid);
+        let radii: Vec<f32> = asteroid.vertices.chunks(3).map(|v| (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt()).collect();
+        assert!(radii.iter().all(|radius| (0.8 - 1e-5..=1.2 + 1e-5).contains(radius)));
+        assert!(radii.iter().any(|radius| (radius - 1.0).abs() > 0.01));
+    }
+    let sphere = ShapeParams::Sphere { subdivisions: 8, mode: SphereMode::Ico, smooth: true };
+    assert_eq!(displaced(sphere.clone(), 3, 0.2).vertices, displaced(sphere.clone(), 3, 0.2).vertices);
+    assert_ne!(displaced(sphere.clone(), 3, 0.2).vertices, displaced(sphere.clone(), 4, 0.2).vertices);
+    assert_eq!(displaced(sphere.clone(), 3, 0.0).vertices, generate_geometry(&sphere, vec![1.0, 1.0, 1.0]).unwrap().vertices);
+
+    // The corners and edges of a cube move together so it stays closed, and its sides keep their hard edges.
+    let cube = displaced(ShapeParams::Cube { subdivisions: 6 }, 1, 0.1);
+    assert_normals_match_winding(&cube);
+    assert_watertight(&cube);
+    let corner: Vec<&[f32]> = cube.vertices.chunks(3).zip(cube.normals.chunks(3)).filter(|(vertex, _)| vertex[0] > 0.45 && vertex[1] > 0.45 && vertex[2] > 0.45).map(|(_, normal)| normal).collect();
+    assert_eq!(corner.len(), 3);
+    assert!(corner.iter().all(|normal| corner.iter().filter(|other| other == &normal).count() == 1));
+
+    // The displacement doesn't change which shape it is, so exports keep the name of the shape.
+    let params = ShapeParams::Displaced { base: Box::new(sphere.clone()), displacement: Displacement::default_params() };
+    assert_eq!(params.shape(), Shape::Sphere);
+    assert!(export_shape(&params, vec![1.0, 1.0, 1.0], 6, String::new()).unwrap().contains("o Sphere"));
+
+    let invalid = ShapeParams::Displaced { base: Box::new(sphere), displacement: Displacement { seed: 1, amplitude: 0.1, frequency: 2.0, octaves: 0 } };
+    assert_eq!(generate_geometry(&invalid, vec![1.0, 1.0, 1.0]).unwrap_err(), ShapeError::InvalidCount { name: "octaves", value: 0.0, min: 1.0, max: 16.0 });
+}
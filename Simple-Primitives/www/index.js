@@ -8,15 +8,15 @@ webClient.initCallBacks();
 // These are all the inputs from the editor screen, we want to set them here because the are used in different parts of this file.
 const inputShape = document.getElementById('dropdown-shapes');
 
+// The shapes come from the wasm module, so a new shape shows up in the dropdown without changing this file.
+wasm._shape_names().forEach((name, index) => inputShape.add(new Option(name, index)));
+
 const inputScaleX = document.getElementById('inputScaleX');
 const inputScaleY = document.getElementById('inputScaleY');
 const inputScaleZ = document.getElementById('inputScaleZ');
 
-const inputSubdivisions = document.getElementById('inputSubdivisions');
-const inputSides = document.getElementById('inputSides');
-const inputRadius = document.getElementById('inputRadius');
-const inputInnerRadius = document.getElementById('inputInnerRadius');
-const inputOuterRadius = document.getElementById('inputOuterRadius');
+// The inputs for the parameters of the selected shape live in this element, see updateConfigurations().
+const parameters = document.getElementById('parameters');
 
 // ==== GENERATE
 const btnGenerate = document.getElementById('btnGenerate');
@@ -25,71 +25,154 @@ btnGenerate.onclick = function() {
   generate();
 }
 
-// Here we determine which shape we need to generate and we pass the right settings to the generateShape function.
+// Here we generate the selected shape with the values of the parameter inputs.
 function generate() {
-  switch(inputShape.value) {
-    case 'plane':
-      generateShape([inputSubdivisions.value]);
-      break;
-    case 'disk':
-      generateShape([inputSides.value, inputRadius.value]);
-      break;
-    case 'cube':
-      generateShape([inputSubdivisions.value]);
-      break;
-    case 'sphere':
-      generateShape([inputSubdivisions.value]);
-      break;
-    case 'cylinder':
-      generateShape([inputSides.value, inputRadius.value]);
-      break;
-    case 'tube':
-      generateShape([inputSides.value, inputInnerRadius.value, inputOuterRadius.value]);
-    }
+  generateShape(shapeParams());
 
   webClient.drawScene();
 }
 
-function generateShape(args) {
-  webClient.generate(inputShape.selectedIndex, [inputScaleX.value, inputScaleY.value, inputScaleZ.value], args);
+function scale() {
+  return [inputScaleX.value, inputScaleY.value, inputScaleZ.value];
+}
+
+// Returns the values of the parameter inputs as an object with a property per parameter name, which is how the wasm module reads them.
+function shapeParams() {
+  let params = {};
+
+  for (let input of parameters.querySelectorAll('input, select')) {
+    if (input.tagName === 'SELECT') {
+      params[input.name] = input.selectedIndex;
+    }
+    else {
+      params[input.name] = input.type === 'checkbox' ? input.checked : input.valueAsNumber;
+    }
+  }
+
+  return params;
+}
+
+// Invalid settings are thrown as a ShapeError, in which case the current shape stays on screen and the user is told what is wrong.
+function generateShape(params) {
+  try {
+    webClient.generate(inputShape.selectedIndex, scale(), params);
+  }
+  catch (error) {
+    showShapeError(error);
+  }
+}
+
+// Only errors about the settings are shown to the user, anything else is a bug and is thrown again.
+function showShapeError(error) {
+  if (error.name !== 'ShapeError') {
+    throw error;
+  }
+
+  alert("Can't make this shape, " + error.message);
 }
 
 // ==== EXPORT
 const btnExport = document.getElementById('btnExport');
-
-let fileContents;
+const inputFormat = document.getElementById('dropdown-formats');
+const inputColors = document.getElementById('dropdown-colors');
+const inputColor = document.getElementById('inputColor');
+const inputPrecision = document.getElementById('inputPrecision');
+const inputMaterial = document.getElementById('inputMaterial');
 
 btnExport.onclick = function () {
-  switch(inputShape.value) {
-    case 'plane':
-      fileContents = exportShape([inputSubdivisions.value]);
+  try {
+    exportAs(shapeParams());
+  }
+  catch (error) {
+    showShapeError(error);
+  }
+}
+
+// Downloads the shape in the selected format.
+function exportAs(params) {
+  switch(inputFormat.value) {
+    case 'obj':
+      // The .mtl file is downloaded next to the .obj file, which references it by name.
+      if (inputMaterial.checked) {
+        download("export.obj", new Blob([exportShape(params, "export.mtl")], { type: 'text/plain' }));
+        download("export.mtl", new Blob([wasm._export_material()], { type: 'text/plain' }));
+      }
+      else {
+        download("export.obj", new Blob([exportShape(params, "")], { type: 'text/plain' }));
+      }
+      break;
+    case 'stl-binary':
+      download("export.stl", new Blob([exportShapeStl(params, true)], { type: 'application/octet-stream' }));
+      break;
+    case 'stl-ascii':
+      download("export.stl", new Blob([exportShapeStl(params, false)], { type: 'text/plain' }));
       break;
-    case 'disk':
-      fileContents = exportShape([inputSides.value, inputRadius.value]);
+    case 'gltf':
+      download("export.gltf", new Blob([exportShapeGltf(params, false)], { type: 'model/gltf+json' }));
       break;
-    case 'cube':
-      fileContents = exportShape([inputSubdivisions.value]);
+    case 'glb':
+      download("export.glb", new Blob([exportShapeGltf(params, true)], { type: 'model/gltf-binary' }));
       break;
-    case 'sphere':
-      fileContents = exportShape([inputSubdivisions.value]);
+    case 'ply-ascii':
+      download("export.ply", new Blob([exportShapePly(params, false)], { type: 'text/plain' }));
+      break;
+    case 'ply-binary':
+      download("export.ply", new Blob([exportShapePly(params, true)], { type: 'application/octet-stream' }));
+  }
+}
+
+function exportShape(params, materialFile) {
+  return wasm._export_shape(inputShape.selectedIndex, scale(), params, inputPrecision.value, materialFile);
+}
+
+// Returns the STL file as an Uint8Array.
+function exportShapeStl(params, binary) {
+  return wasm._export_shape_stl(inputShape.selectedIndex, scale(), params, binary);
+}
+
+// Returns the glTF file as an Uint8Array, binary gives a .glb file.
+function exportShapeGltf(params, binary) {
+  return wasm._export_shape_gltf(inputShape.selectedIndex, scale(), params, binary);
+}
+
+// Returns the PLY file with normals as an Uint8Array. The color input holds a hex string like #c0c0c0 which is passed as three bytes.
+function exportShapePly(params, binary) {
+  let colorScheme;
+
+  switch(inputColors.value) {
+    case 'none':
+      colorScheme = wasm.ColorScheme.None;
       break;
-    case 'cylinder':
-      fileContents = exportShape([inputSides.value, inputRadius.value]);
+    case 'alternating':
+      colorScheme = wasm.ColorScheme.Alternating;
       break;
-    case 'tube':
-      fileContents = exportShape([inputSides.value, inputInnerRadius.value, inputOuterRadius.value]);
+    case 'solid':
+      colorScheme = wasm.ColorScheme.Solid;
   }
-  
-  download("export.obj", fileContents);
+
+  let hex = inputColor.value;
+  let color = [1, 3, 5].map(i => parseInt(hex.substr(i, 2), 16));
+
+  return wasm._export_shape_ply(inputShape.selectedIndex, scale(), params, binary, true, colorScheme, color);
 }
 
-function exportShape(args) {
-  return wasm._export_shape(inputShape.selectedIndex, [inputScaleX.value, inputScaleY.value, inputScaleZ.value], args);
+// The precision and material options are only used by the OBJ export and the color options only by the PLY export, so we only show them when that format is selected.
+const itemObj = document.getElementsByClassName('obj');
+const itemPly = document.getElementsByClassName('ply');
+
+function updateFormat() {
+  setDisplay(itemObj, inputFormat.value === 'obj');
+  setDisplay(itemPly, inputFormat.value.startsWith('ply'));
 }
 
-function download(filename, text) {
+inputFormat.addEventListener('change', updateFormat);
+updateFormat();
+
+// Downloads the blob as a file. We use an object url instead of a data url so binary files are downloaded correctly.
+function download(filename, blob) {
+  let url = URL.createObjectURL(blob);
   let element = document.createElement('a');
-  element.setAttribute('href', 'data:text/plain;charset=utf-8,' + encodeURIComponent(text));
+  element.setAttribute('href', url);
   element.setAttribute('download', filename);
 
   element.style.display = 'none';
@@ -98,6 +181,30 @@ function download(filename, text) {
   element.click();
 
   document.body.removeChild(element);
+  URL.revokeObjectURL(url);
+}
+
+// ==== IMPORT
+const inputImport = document.getElementById('inputImport');
+
+// The OBJ file is read as text and shown in place of the generated shape until a shape is generated again.
+inputImport.onchange = function () {
+  let file = inputImport.files[0];
+  if (!file) {
+    return;
+  }
+
+  file.text().then(text => {
+    try {
+      webClient.load_obj(text);
+      webClient.drawScene();
+    }
+    catch (error) {
+      alert(file.name + ", " + error.message);
+    }
+
+    inputImport.value = '';
+  });
 }
 
 // ==== ANIMATION LOOP
@@ -108,8 +215,6 @@ const animate = function() {
 }
 requestAnimationFrame(animate);
 
-generate();
-
 // ==== NAVBAR
 const navbarShapes = document.getElementById('navbar-shapes');
 const navbarAbout = document.getElementById('navbar-about');
@@ -154,7 +259,6 @@ shapesTube.onclick = () => shapeOnClick(5);
 
 function shapeOnClick(index) {
   inputShape.selectedIndex = index;
-  generate();
   updateConfigurations();
   sectionEditor.scrollIntoView(true);
 }
@@ -185,67 +289,61 @@ resize();
 // ==== ONCHANGE
 // This section ensures that the right input elements are displayed for each shape.
 
-const itemSubdivisions = document.getElementsByClassName('subdivisions');
-const itemRadius = document.getElementsByClassName('radius');
-const itemSides = document.getElementsByClassName('sides');
-const itemInnerRadius = document.getElementsByClassName('innerRadius');
-const itemOuterRadius = document.getElementsByClassName('outerRadius');
+// The last value of every parameter by name, so values that shapes share, like the sides of a disk and a cylinder, are kept when switching shapes.
+const parameterValues = {};
 
 inputShape.addEventListener('change', updateConfigurations);
 
 updateConfigurations();
 
+// Replaces the parameter inputs with the ones in the schema of the selected shape and generates the shape.
 function updateConfigurations() {
-  console.log('on change');
-
-  switch(inputShape.value) {
-    case 'plane':
-      setDisplay(itemSubdivisions, true);
-      setDisplay(itemRadius, false);
-      setDisplay(itemSides, false);
-      setDisplay(itemInnerRadius, false);
-      setDisplay(itemOuterRadius, false);
-      break;
-    case 'disk':
-      setDisplay(itemSubdivisions, false);
-      setDisplay(itemRadius, true);
-      setDisplay(itemSides, true);
-      setDisplay(itemInnerRadius, false);
-      setDisplay(itemOuterRadius, false);
-      break;
-    case 'cube':
-      setDisplay(itemSubdivisions, true);
-      setDisplay(itemRadius, false);
-      setDisplay(itemSides, false);
-      setDisplay(itemInnerRadius, false);
-      setDisplay(itemOuterRadius, false);
-      break;
-    case 'sphere':
-      setDisplay(itemSubdivisions, true);
-      setDisplay(itemRadius, false);
-      setDisplay(itemSides, false);
-      setDisplay(itemInnerRadius, false);
-      setDisplay(itemOuterRadius, false);
-      break;
-    case 'cylinder':
-      setDisplay(itemSubdivisions, false);
-      setDisplay(itemRadius, true);
-      setDisplay(itemSides, true);
-      setDisplay(itemInnerRadius, false);
-      setDisplay(itemOuterRadius, false);
-      break;
-    case 'tube':
-      setDisplay(itemSubdivisions, false);
-      setDisplay(itemRadius, false);
-      setDisplay(itemSides, true);
-      setDisplay(itemInnerRadius, true);
-      setDisplay(itemOuterRadius, true);
-      break;
+  Object.assign(parameterValues, shapeParams());
+  parameters.innerHTML = '';
+
+  for (let info of wasm._shape_schema(inputShape.selectedIndex)) {
+    let label = document.createElement('label');
+    label.textContent = info.label;
+
+    let input = document.createElement(info.type === 'choice' ? 'select' : 'input');
+    input.name = info.name;
+    let value = info.name in parameterValues ? parameterValues[info.name] : info.default;
+
+    if (info.type === 'choice') {
+      info.options.forEach((option, index) => input.add(new Option(option, index)));
+      // Another shape can have a choice with the same name but fewer options.
+      input.selectedIndex = value < info.options.length ? value : info.default;
+    }
+    else if (info.type === 'bool') {
+      input.type = 'checkbox';
+      input.checked = Boolean(value);
+    }
+    else {
+      input.type = 'number';
+      input.value = value;
+      input.min = info.min;
+      input.step = info.type === 'int' ? 1 : 'any';
+      if (isFinite(info.max)) {
+        input.max = info.max;
+      }
+    }
+
+    parameters.appendChild(uiItem(label));
+    parameters.appendChild(uiItem(input));
   }
 
   generate();
 }
 
+// Wraps the element in a div with the ui-item class, like the other inputs of the editor.
+function uiItem(element) {
+  let item = document.createElement('div');
+  item.className = 'ui-item';
+  item.appendChild(element);
+
+  return item;
+}
+
 // Set the visibility of all the elements in an array. Indicate with show if it is visible or not.
 function setDisplay(elements, show) {
   for (let i = 0; i < elements.length; i++) {